@@ -0,0 +1,106 @@
+use criterion::*;
+use fxhash::FxHashSet;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bitris::prelude::*;
+
+// Counts the bytes currently allocated to compare the memory of the sets.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Returns the bytes held by the value that the function builds.
+fn retained_bytes<S>(build: impl FnOnce() -> S) -> usize {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = build();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+    drop(value);
+    after - before
+}
+
+// Low boards like those in PC searches, generated by a fixed LCG.
+fn low_boards(count: usize, height: u32) -> Vec<Board64> {
+    let mut seed = 0x2545F4914F6CDD1Du64;
+    let mask = (1u64 << height) - 1;
+    (0..count)
+        .map(|_| {
+            Board64::new(std::array::from_fn(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (seed >> 32) & mask
+            }))
+        })
+        .collect()
+}
+
+fn bench_board_set(c: &mut Criterion) {
+    for height in [4, 12] {
+        let boards = low_boards(100_000, height);
+
+        let fx_hash_set_bytes = retained_bytes(|| boards.iter().copied().collect::<FxHashSet<_>>());
+        let board_set_bytes = retained_bytes(|| {
+            let mut set = BoardSet::new();
+            for &board in &boards {
+                set.insert(board);
+            }
+            set
+        });
+        println!(
+            "board_set/height-{}/memory: fx_hash_set {} bytes, board_set {} bytes",
+            height, fx_hash_set_bytes, board_set_bytes,
+        );
+
+        let mut group = c.benchmark_group(format!("board_set/height-{}", height));
+        group.bench_function("fx_hash_set", |b| {
+            b.iter(|| {
+                let mut set = FxHashSet::<Board64>::default();
+                for &board in &boards {
+                    set.insert(board);
+                }
+                let found = boards.iter().filter(|board| set.contains(board)).count();
+                black_box((set.len(), found));
+            })
+        });
+        group.bench_function("board_set", |b| {
+            b.iter(|| {
+                let mut set = BoardSet::new();
+                for &board in &boards {
+                    set.insert(board);
+                }
+                let found = boards.iter().filter(|board| set.contains(board)).count();
+                black_box((set.len(), found));
+            })
+        });
+        group.finish()
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default();
+    targets = bench_board_set);
+criterion_main!(benches);
@@ -3,6 +3,7 @@ use std::str::FromStr;
 use criterion::*;
 use std::hint::black_box;
 
+use bitris::piece;
 use bitris::prelude::*;
 
 #[derive(Debug)]
@@ -783,8 +784,139 @@ fn bench_moves_in_srs(c: &mut Criterion) {
     });
 }
 
+fn minimized_moves_by_board<B: MoveBits>(board: Board<B>, spawn: CcPlacement, expected: usize) {
+    let moves = srs::generate_minimized_moves(AllowMove::Softdrop, board, spawn.into());
+    assert_eq!(moves.len(), expected);
+}
+
+fn bench_moves_by_board_size(c: &mut Criterion) {
+    use Shape::*;
+
+    let board = Board64::from_str(
+        "\
+        ##.....###\
+        ##....####\
+        ##...#####\
+        ##....####\
+    ",
+    )
+    .unwrap();
+
+    // The spawn must be inside the smallest board.
+    let spawn = cc(4, 6);
+    let expected_minimized_moves = vec![(T, 35), (I, 18), (O, 9)];
+
+    let mut group = c.benchmark_group("moves_by_board_size");
+
+    for (shape, expected) in expected_minimized_moves {
+        let spawn = shape.with(Orientation::North).with(spawn);
+        group.bench_function(BenchmarkId::new("board8", shape), |b| {
+            let board = Board8::shrink_from(board);
+            b.iter(|| {
+                minimized_moves_by_board(board, spawn, expected);
+                black_box(());
+            })
+        });
+        group.bench_function(BenchmarkId::new("board16", shape), |b| {
+            let board = Board16::shrink_from(board);
+            b.iter(|| {
+                minimized_moves_by_board(board, spawn, expected);
+                black_box(());
+            })
+        });
+        group.bench_function(BenchmarkId::new("board32", shape), |b| {
+            let board = Board32::shrink_from(board);
+            b.iter(|| {
+                minimized_moves_by_board(board, spawn, expected);
+                black_box(());
+            })
+        });
+        group.bench_function(BenchmarkId::new("board64", shape), |b| {
+            b.iter(|| {
+                minimized_moves_by_board(board, spawn, expected);
+                black_box(());
+            })
+        });
+    }
+
+    group.finish()
+}
+
+fn bench_moves_batch(c: &mut Criterion) {
+    use Shape::*;
+
+    let board = Board64::from_str(
+        "\
+        ..........\
+        ....####..\
+        ....######\
+        ##.#######\
+    ",
+    )
+    .unwrap();
+    let spawns = [T, I, O, L, J, S, Z].map(|shape| shape.with(Orientation::North).with(bl(4, 20)));
+    let rules = MoveRules::srs(AllowMove::Softdrop);
+
+    let mut group = c.benchmark_group("moves_batch");
+
+    group.bench_function("one_by_one", |b| {
+        b.iter(|| {
+            let sets = spawns.map(|spawn| rules.generate_minimized_moves_set(board, spawn));
+            black_box(sets);
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| {
+            let sets = rules.generate_minimized_moves_batch(board, spawns);
+            black_box(sets);
+        })
+    });
+
+    // The spawns of the same shape share the free spaces.
+    let same_shape_spawns = [3, 4, 5].map(|x| T.with(Orientation::North).with(bl(x, 20)));
+    group.bench_function("one_by_one_same_shape", |b| {
+        b.iter(|| {
+            let sets =
+                same_shape_spawns.map(|spawn| rules.generate_minimized_moves_set(board, spawn));
+            black_box(sets);
+        })
+    });
+    group.bench_function("batch_same_shape", |b| {
+        b.iter(|| {
+            let sets = rules.generate_minimized_moves_batch(board, same_shape_spawns);
+            black_box(sets);
+        })
+    });
+
+    group.finish()
+}
+
+// The rules without the gravity and the limit must stay on the plain path.
+fn bench_moves_with_lock_delay(c: &mut Criterion) {
+    let board = Board64::blank();
+    let spawn = piece!(TN).with(bl(4, 20));
+    let plain = MoveRules::srs(AllowMove::Softdrop);
+
+    let mut group = c.benchmark_group("moves_with_lock_delay");
+    for (name, rules) in [
+        ("plain", plain),
+        ("gravity_0", plain.with_gravity(0)),
+        ("gravity_1", plain.with_gravity(1)),
+        ("move_reset_limit_15", plain.with_move_reset_limit(15)),
+        (
+            "twenty_g_move_reset_limit_15",
+            MoveRules::srs(AllowMove::TwentyG).with_move_reset_limit(15),
+        ),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(rules.generate_all_moves(board, spawn)))
+        });
+    }
+    group.finish()
+}
+
 criterion_group!(
     name = benches;
     config = Criterion::default();
-    targets = bench_moves_in_srs);
+    targets = bench_moves_in_srs, bench_moves_by_board_size, bench_moves_batch, bench_moves_with_lock_delay);
 criterion_main!(benches);
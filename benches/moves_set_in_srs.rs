@@ -0,0 +1,109 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::*;
+use std::hint::black_box;
+
+use bitris::prelude::*;
+
+// Counts the allocations to check that the set generation does not allocate.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Returns the number of allocations while the function runs.
+fn count_allocations<S>(f: impl FnOnce() -> S) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn bench_moves_set_in_srs(c: &mut Criterion) {
+    let boards = [
+        ("empty", Board64::blank()),
+        (
+            "pco",
+            Board64::from_str(
+                "\
+                ##.....###\
+                ##....####\
+                ##...#####\
+                ##....####\
+            ",
+            )
+            .unwrap(),
+        ),
+        (
+            "dt",
+            Board64::from_str(
+                "\
+                ....#.....\
+                ...##.##..\
+                #..####...\
+                #######.##\
+                #######..#\
+                ######...#\
+                #######.##\
+                #######.##\
+            ",
+            )
+            .unwrap(),
+        ),
+    ];
+
+    for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop] {
+        let rules = MoveRules::srs(allow_move);
+
+        for (name, board) in boards {
+            // The sets are held as bits, so the generation must not allocate.
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                let allocations = count_allocations(|| {
+                    (
+                        rules.generate_all_moves_set(board, spawn),
+                        rules.generate_minimized_moves_set(board, spawn),
+                    )
+                });
+                assert_eq!(allocations, 0, "{:?} {} {:?}", allow_move, name, shape);
+            }
+
+            let mut group = c.benchmark_group(format!("moves_set_{:?}_{}", allow_move, name));
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                group.bench_function(BenchmarkId::new("all_moves_set", shape), |b| {
+                    b.iter(|| black_box(rules.generate_all_moves_set(board, spawn)))
+                });
+                group.bench_function(BenchmarkId::new("minimized_moves_set", shape), |b| {
+                    b.iter(|| black_box(rules.generate_minimized_moves_set(board, spawn)))
+                });
+            }
+            group.finish()
+        }
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default();
+    targets = bench_moves_set_in_srs);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+use std::str::FromStr;
+
+use criterion::*;
+use std::hint::black_box;
+
+use bitris::prelude::*;
+
+#[derive(Debug)]
+struct PerftBenchmarkData {
+    name: String,
+    board: Board64,
+    hold: Option<Shape>,
+    queue: Vec<Shape>,
+    depth: usize,
+}
+
+fn bench_perft(c: &mut Criterion) {
+    use Shape::*;
+
+    let benchmarks = vec![
+        PerftBenchmarkData {
+            name: "empty-2".to_string(),
+            board: Board64::blank(),
+            hold: None,
+            queue: vec![T, I],
+            depth: 2,
+        },
+        PerftBenchmarkData {
+            name: "pco-2".to_string(),
+            board: Board64::from_str(
+                "\
+                ##.....###\
+                ##....####\
+                ##...#####\
+                ##....####\
+            ",
+            )
+            .unwrap(),
+            hold: Some(I),
+            queue: vec![L, J],
+            depth: 2,
+        },
+    ];
+
+    let move_rules = MoveRules::srs(AllowMove::Softdrop);
+    let perft = Perft::new(&move_rules, GameRules::guideline());
+
+    let mut group = c.benchmark_group("perft");
+    for data in benchmarks {
+        group.bench_function(data.name.as_str(), |b| {
+            b.iter(|| {
+                let count = perft.count_boards(data.board, data.hold, &data.queue, data.depth);
+                black_box(count);
+            })
+        });
+    }
+    group.finish()
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default();
+    targets = bench_perft);
+criterion_main!(benches);
@@ -115,15 +115,16 @@ fn format_offset(offset: Offset) -> String {
 fn generate_free(path: &str) {
     let content = begin(|b| {
         b._comment_block("It's auto generated.");
-        b._use("crate::internal_moves::u64::free_space::FreeSpace64");
+        b._use("crate::internal_moves::free_space::FreeSpace");
+        b._use("crate::internal_moves::MoveBits");
         b._use("crate::pieces::{Shape, Orientation, Piece}");
 
         b.newline();
         b.println("#[inline(always)]");
         b._pub_fn(
-            "to_free_spaces",
-            "free_space_block: FreeSpace64, shape: Shape",
-            "[FreeSpace64; 4]",
+            "to_free_spaces<T: MoveBits>",
+            "free_space_block: FreeSpace<T>, shape: Shape",
+            "[FreeSpace<T>; 4]",
             |b| {
                 b._match("shape", |b| {
                     for shape in Shape::all_iter() {
@@ -150,9 +151,9 @@ fn generate_free(path: &str) {
         b.newline();
         b.println("#[inline(always)]");
         b._pub_fn(
-            "to_free_space",
-            "free_space_block: FreeSpace64, piece: Piece",
-            "FreeSpace64",
+            "to_free_space<T: MoveBits>",
+            "free_space_block: FreeSpace<T>, piece: Piece",
+            "FreeSpace<T>",
             |b| {
                 b._match("piece.shape", |b| {
                     for shape in Shape::all_iter() {
@@ -187,9 +188,9 @@ fn generate_free(path: &str) {
                 b.newline();
                 b.println("#[inline(always)]");
                 b._fn(
-                    function_name.to_lowercase().as_str(),
-                    "space: FreeSpace64",
-                    "FreeSpace64",
+                    format!("{}<T: MoveBits>", function_name.to_lowercase()).as_str(),
+                    "space: FreeSpace<T>",
+                    "FreeSpace<T>",
                     |b| {
                         fn format_space(offset: Offset, clone: bool) -> String {
                             let mut line = String::with_capacity(256);
@@ -230,5 +231,5 @@ fn generate_free(path: &str) {
 }
 
 fn main() {
-    generate_free("src/internal_moves/u64/free.rs");
+    generate_free("src/internal_moves/free.rs");
 }
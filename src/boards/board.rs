@@ -2,7 +2,7 @@ use std::cmp;
 use std::fmt;
 use std::str::FromStr;
 
-use crate::boards::Lines;
+use crate::boards::{Lines, Lines128};
 use crate::coordinates::{xy, Location};
 
 /// Ceiling of the board.
@@ -13,6 +13,9 @@ pub trait Ceiling: Sized {
 
 /// Low level board operations.
 pub trait BoardOp: Ceiling {
+    /// The key of the rows. `Lines` for boards up to 64 rows, `Lines128` for `Board128`.
+    type Lines: Copy + Eq + fmt::Debug + From<Lines> + Into<Lines128>;
+
     /// Returns the board height.
     #[inline(always)]
     fn ceiling(&self) -> u32 {
@@ -43,16 +46,16 @@ pub trait BoardOp: Ceiling {
     fn count_blocks(&self) -> u32;
 
     /// Returns as a key the row in which one or more blocks exist.
-    fn used_rows(&self) -> Lines;
+    fn used_rows(&self) -> Self::Lines;
 
     /// Returns as key the rows that are all filled with blocks.
-    fn filled_rows(&self) -> Lines;
+    fn filled_rows(&self) -> Self::Lines;
 
     /// Remove specified rows only.
-    fn clear_lines_partially(&mut self, lines: Lines);
+    fn clear_lines_partially(&mut self, lines: Self::Lines);
 
     /// Remove rows that are all filled with blocks.
-    fn clear_lines(&mut self) -> Lines;
+    fn clear_lines(&mut self) -> Self::Lines;
 
     /// Swap all blocks and spaces.
     fn invert(&mut self);
@@ -104,7 +107,7 @@ pub trait ShrinkFrom<T>: Sized {
 /// It has the position of the blocks as a bit array.
 /// Bit arrays record positions vertically.
 /// Therefore, the size of the array is 10 (=width).
-/// Generic type can be unsigned int(u8-u64).
+/// Generic type can be unsigned int(u8-u128).
 /// This bit width corresponds to the height of the board.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Board<T> {
@@ -248,6 +251,41 @@ impl Board<u64> {
     }
 }
 
+impl Board<u128> {
+    #[inline]
+    pub const fn new(cols: [u128; 10]) -> Self {
+        Self { cols }
+    }
+
+    #[inline]
+    pub const fn blank() -> Self {
+        Self { cols: [0; 10] }
+    }
+
+    /// Returns a board filled up to a specified height.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Board128::filled_up_to(0).count_blocks(), 0);
+    /// assert_eq!(Board128::filled_up_to(1).count_blocks(), 10);
+    /// assert_eq!(Board128::filled_up_to(5).count_blocks(), 50);
+    /// ```
+    #[inline]
+    pub const fn filled_up_to(height: u8) -> Self {
+        Self {
+            cols: [(1 << height) - 1; 10],
+        }
+    }
+
+    /// Returns a new board after clearing lines.
+    #[inline]
+    #[must_use]
+    pub fn after_clearing(&self) -> Self {
+        let mut board = *self;
+        board.clear_lines();
+        board
+    }
+}
+
 impl<T> fmt::Display for Board<T>
 where
     Board<T>: BoardOp,
@@ -408,7 +446,15 @@ board_from!( u8, into u64);
 board_from!(u16, into u32);
 board_from!(u16, into u64);
 board_from!(u32, into u64);
-
+board_from!( u8, into u128);
+board_from!(u16, into u128);
+board_from!(u32, into u128);
+board_from!(u64, into u128);
+
+board_shrink_from!(u128, into u64);
+board_shrink_from!(u128, into u32);
+board_shrink_from!(u128, into u16);
+board_shrink_from!(u128, into u8 );
 board_shrink_from!(u64, into u32);
 board_shrink_from!(u64, into u16);
 board_shrink_from!(u64, into u8 );
@@ -552,7 +598,16 @@ impl Ceiling for Board<u64> {
     }
 }
 
+impl Ceiling for Board<u128> {
+    #[inline(always)]
+    fn ceiling() -> u32 {
+        128
+    }
+}
+
 impl BoardOp for Board<u8> {
+    type Lines = Lines;
+
     #[inline]
     fn well_top(&self) -> u32 {
         self.ceiling() - used_row_key!(self.cols).leading_zeros()
@@ -637,6 +692,8 @@ impl BoardOp for Board<u8> {
 }
 
 impl BoardOp for Board<u16> {
+    type Lines = Lines;
+
     #[inline]
     fn well_top(&self) -> u32 {
         self.ceiling() - used_row_key!(self.cols).leading_zeros()
@@ -721,6 +778,8 @@ impl BoardOp for Board<u16> {
 }
 
 impl BoardOp for Board<u32> {
+    type Lines = Lines;
+
     #[inline]
     fn well_top(&self) -> u32 {
         self.ceiling() - used_row_key!(self.cols).leading_zeros()
@@ -805,6 +864,8 @@ impl BoardOp for Board<u32> {
 }
 
 impl BoardOp for Board<u64> {
+    type Lines = Lines;
+
     fn well_top(&self) -> u32 {
         self.ceiling() - used_row_key!(self.cols).leading_zeros()
     }
@@ -887,6 +948,92 @@ impl BoardOp for Board<u64> {
     }
 }
 
+impl BoardOp for Board<u128> {
+    type Lines = Lines128;
+
+    #[inline]
+    fn well_top(&self) -> u32 {
+        self.ceiling() - used_row_key!(self.cols).leading_zeros()
+    }
+
+    #[inline]
+    fn set_at(&mut self, location: Location) {
+        set_at!(self.cols, location)
+    }
+
+    #[inline]
+    fn unset_at(&mut self, location: Location) {
+        unset_at!(self.cols, location)
+    }
+
+    #[inline]
+    fn is_occupied_at(&self, location: Location) -> bool {
+        is_occupied_at!(self.cols, location)
+    }
+
+    #[inline]
+    fn is_free_at(&self, location: Location) -> bool {
+        is_free_at!(self.cols, location)
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        is_empty!(self.cols)
+    }
+
+    #[inline]
+    fn count_blocks(&self) -> u32 {
+        count_blocks!(self.cols)
+    }
+
+    #[inline]
+    fn used_rows(&self) -> Lines128 {
+        Lines128::new(used_row_key!(self.cols))
+    }
+
+    #[inline]
+    fn filled_rows(&self) -> Lines128 {
+        Lines128::new(filled_row_key!(self.cols))
+    }
+
+    #[inline]
+    fn clear_lines_partially(&mut self, lines: Lines128) {
+        clear_lines!(self.cols, lines.key)
+    }
+
+    #[inline]
+    fn clear_lines(&mut self) -> Lines128 {
+        let key = filled_row_key!(self.cols);
+        clear_lines!(self.cols, key);
+        Lines128::new(key)
+    }
+
+    #[inline]
+    fn invert(&mut self) {
+        invert!(self.cols)
+    }
+
+    #[inline]
+    fn mirror(&mut self) {
+        mirror!(self.cols)
+    }
+
+    #[inline]
+    fn overlaps(&self, other: &Self) -> bool {
+        overlaps!(self.cols, other.cols)
+    }
+
+    #[inline]
+    fn merge(&mut self, other: &Self) {
+        merge!(self.cols, other.cols)
+    }
+
+    #[inline]
+    fn remove_all(&mut self, other: &Self) {
+        remove_all!(self.cols, other.cols)
+    }
+}
+
 /// An alias for `BoardT<u8>`
 pub type Board8 = Board<u8>;
 
@@ -899,6 +1046,9 @@ pub type Board32 = Board<u32>;
 /// An alias for `BoardT<u64>`
 pub type Board64 = Board<u64>;
 
+/// An alias for `BoardT<u128>`
+pub type Board128 = Board<u128>;
+
 #[cfg(test)]
 mod tests {
     use std::fmt;
@@ -930,12 +1080,18 @@ mod tests {
         Board64::blank()
     }
 
+    #[fixture]
+    pub fn board128() -> Board128 {
+        Board128::blank()
+    }
+
     #[test]
     fn size_of_boards() {
         assert_eq!(size_of::<Board8>(), 10);
         assert_eq!(size_of::<Board16>(), 20);
         assert_eq!(size_of::<Board32>(), 40);
         assert_eq!(size_of::<Board64>(), 80);
+        assert_eq!(size_of::<Board128>(), 160);
     }
 
     #[test]
@@ -950,15 +1106,21 @@ mod tests {
         assert_eq!(board16().ceiling(), 16);
         assert_eq!(board32().ceiling(), 32);
         assert_eq!(board64().ceiling(), 64);
+        assert_eq!(board128().ceiling(), 128);
     }
 
     #[test]
     fn from_board_shrink() {
-        let mut board = Board64::blank();
+        let mut board = Board128::blank();
         board.set_at(xy(0, 0));
         board.set_at(xy(0, 8));
         board.set_at(xy(0, 16));
         board.set_at(xy(0, 32));
+        board.set_at(xy(0, 64));
+        assert_eq!(board.count_blocks(), 5);
+        assert_eq!(board.ceiling(), 128);
+
+        let board = Board64::shrink_from(board);
         assert_eq!(board.count_blocks(), 4);
         assert_eq!(board.ceiling(), 64);
 
@@ -993,6 +1155,10 @@ mod tests {
         let board = Board64::from(board);
         assert_eq!(board.count_blocks(), 1);
         assert_eq!(board.ceiling(), 64);
+
+        let board = Board128::from(board);
+        assert_eq!(board.count_blocks(), 1);
+        assert_eq!(board.ceiling(), 128);
     }
 
     #[template]
@@ -1001,6 +1167,7 @@ mod tests {
     #[case::board16(board16())]
     #[case::board32(board32())]
     #[case::board64(board64())]
+    #[case::board128(board128())]
     fn all_boards(#[case] mut board: impl BoardOp) {}
 
     #[apply(all_boards)]
@@ -1038,16 +1205,16 @@ mod tests {
             board.set_at(xy(x, 0));
         }
         let cleared = board.clear_lines();
-        assert_eq!(cleared.count(), 1);
+        assert_eq!(cleared, Lines::new_at(0).into());
         assert_eq!(board.count_blocks(), 0);
 
         board.invert();
-        let row_key = board.filled_rows();
+        let row_key: Lines128 = board.filled_rows().into();
         assert_eq!(row_key.count(), board.ceiling());
 
         board.clear_lines();
-        let row_key = board.filled_rows();
-        assert_eq!(row_key.count(), 0);
+        let row_key: Lines128 = board.filled_rows().into();
+        assert!(row_key.is_blank());
     }
 
     #[apply(all_boards)]
@@ -1079,8 +1246,26 @@ mod tests {
         assert_eq!(board.well_top(), board.ceiling());
         assert_eq!(board.count_blocks(), board.ceiling() - 4);
 
-        board.clear_lines_partially(Lines::new(0b11110));
+        board.clear_lines_partially(Lines::new(0b11110).into());
         assert_eq!(board.well_top(), board.ceiling() - 4);
         assert_eq!(board.count_blocks(), board.ceiling() - 5);
     }
+
+    #[test]
+    fn clear_lines_over_64_rows() {
+        let mut board = Board128::blank();
+        for x in 0..10 {
+            board.set_at(xy(x, 100));
+        }
+        board.set_at(xy(0, 101));
+        board.set_at(xy(0, 127));
+
+        assert_eq!(board.filled_rows(), Lines128::new_at(100));
+        assert_eq!(board.used_rows().ys(), vec![100, 101, 127]);
+        assert_eq!(board.clear_lines(), Lines128::new_at(100));
+        assert_eq!(board.count_blocks(), 2);
+        assert!(board.is_occupied_at(xy(0, 100)));
+        assert!(board.is_occupied_at(xy(0, 126)));
+        assert_eq!(board.well_top(), 127);
+    }
 }
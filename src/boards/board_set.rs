@@ -0,0 +1,391 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::boards::{Board64, BoardOp};
+
+// Boards are packed column by column, using only the rows up to the limit.
+// Low boards, common in PC and opening searches, take 8 or 16 bytes instead of 80 bytes of `Board64`.
+const SMALL_HEIGHT: u32 = 6;
+const MEDIUM_HEIGHT: u32 = 12;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+enum PackedBoard {
+    Small(u64),
+    Medium(u128),
+    Large(Board64),
+}
+
+impl PackedBoard {
+    #[inline]
+    fn pack(board: &Board64) -> Self {
+        let well_top = board.well_top();
+        if well_top <= SMALL_HEIGHT {
+            let key = board.cols.iter().enumerate().fold(0u64, |key, (x, &col)| {
+                key | col << (x as u32 * SMALL_HEIGHT)
+            });
+            PackedBoard::Small(key)
+        } else if well_top <= MEDIUM_HEIGHT {
+            let key = board.cols.iter().enumerate().fold(0u128, |key, (x, &col)| {
+                key | (col as u128) << (x as u32 * MEDIUM_HEIGHT)
+            });
+            PackedBoard::Medium(key)
+        } else {
+            PackedBoard::Large(*board)
+        }
+    }
+}
+
+#[inline]
+fn unpack_small(key: u64) -> Board64 {
+    let mask = (1u64 << SMALL_HEIGHT) - 1;
+    Board64::new(std::array::from_fn(|x| {
+        (key >> (x as u32 * SMALL_HEIGHT)) & mask
+    }))
+}
+
+#[inline]
+fn unpack_medium(key: u128) -> Board64 {
+    let mask = (1u128 << MEDIUM_HEIGHT) - 1;
+    Board64::new(std::array::from_fn(|x| {
+        ((key >> (x as u32 * MEDIUM_HEIGHT)) & mask) as u64
+    }))
+}
+
+/// A set of boards that packs low boards into small keys.
+/// Boards up to 6 rows take 8 bytes, and up to 12 rows take 16 bytes. Higher boards are stored as they are.
+///
+/// It's much smaller than `FxHashSet<Board64>` when many low boards are visited, as in PC searches.
+/// The iteration order is unspecified.
+/// ```
+/// use bitris::prelude::*;
+/// let mut set = BoardSet::new();
+/// assert!(set.insert(Board64::filled_up_to(4)));
+/// assert!(!set.insert(Board64::filled_up_to(4)));
+/// assert!(set.insert(Board64::filled_up_to(20)));
+///
+/// assert_eq!(set.len(), 2);
+/// assert!(set.contains(&Board64::filled_up_to(20)));
+/// assert!(!set.contains(&Board64::blank()));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct BoardSet {
+    small: FxHashSet<u64>,
+    medium: FxHashSet<u128>,
+    large: FxHashSet<Board64>,
+}
+
+impl BoardSet {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the board to the set.
+    /// Returns true if the board was newly inserted.
+    #[inline]
+    pub fn insert(&mut self, board: Board64) -> bool {
+        match PackedBoard::pack(&board) {
+            PackedBoard::Small(key) => self.small.insert(key),
+            PackedBoard::Medium(key) => self.medium.insert(key),
+            PackedBoard::Large(board) => self.large.insert(board),
+        }
+    }
+
+    /// Returns true if the board is in the set.
+    #[inline]
+    pub fn contains(&self, board: &Board64) -> bool {
+        match PackedBoard::pack(board) {
+            PackedBoard::Small(key) => self.small.contains(&key),
+            PackedBoard::Medium(key) => self.medium.contains(&key),
+            PackedBoard::Large(board) => self.large.contains(&board),
+        }
+    }
+
+    /// Removes the board from the set.
+    /// Returns true if the board was present.
+    #[inline]
+    pub fn remove(&mut self, board: &Board64) -> bool {
+        match PackedBoard::pack(board) {
+            PackedBoard::Small(key) => self.small.remove(&key),
+            PackedBoard::Medium(key) => self.medium.remove(&key),
+            PackedBoard::Large(board) => self.large.remove(&board),
+        }
+    }
+
+    /// Returns the count of boards.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.small.len() + self.medium.len() + self.large.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.small.clear();
+        self.medium.clear();
+        self.large.clear();
+    }
+
+    /// Returns an iterator of the boards.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Board64> + '_ {
+        let small = self.small.iter().map(|&key| unpack_small(key));
+        let medium = self.medium.iter().map(|&key| unpack_medium(key));
+        let large = self.large.iter().copied();
+        small.chain(medium).chain(large)
+    }
+}
+
+impl Extend<Board64> for BoardSet {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Board64>>(&mut self, iter: I) {
+        for board in iter {
+            self.insert(board);
+        }
+    }
+}
+
+impl FromIterator<Board64> for BoardSet {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Board64>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// A map from boards that packs low boards into small keys, like `BoardSet`.
+/// The iteration order is unspecified.
+/// ```
+/// use bitris::prelude::*;
+/// let mut map = BoardMap::new();
+/// assert_eq!(map.insert(Board64::filled_up_to(4), 1), None);
+/// assert_eq!(map.insert(Board64::filled_up_to(4), 2), Some(1));
+/// assert_eq!(map.get(&Board64::filled_up_to(4)), Some(&2));
+///
+/// *map.entry_or_insert(Board64::filled_up_to(16), 0) += 10;
+/// assert_eq!(map.get(&Board64::filled_up_to(16)), Some(&10));
+/// assert_eq!(map.len(), 2);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BoardMap<V> {
+    small: FxHashMap<u64, V>,
+    medium: FxHashMap<u128, V>,
+    large: FxHashMap<Board64, V>,
+}
+
+impl<V> Default for BoardMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            small: FxHashMap::default(),
+            medium: FxHashMap::default(),
+            large: FxHashMap::default(),
+        }
+    }
+}
+
+impl<V> BoardMap<V> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the value for the board.
+    /// Returns the old value if the board was present.
+    #[inline]
+    pub fn insert(&mut self, board: Board64, value: V) -> Option<V> {
+        match PackedBoard::pack(&board) {
+            PackedBoard::Small(key) => self.small.insert(key, value),
+            PackedBoard::Medium(key) => self.medium.insert(key, value),
+            PackedBoard::Large(board) => self.large.insert(board, value),
+        }
+    }
+
+    /// Returns the value for the board, inserting the default value if the board is not present.
+    #[inline]
+    pub fn entry_or_insert(&mut self, board: Board64, default: V) -> &mut V {
+        match PackedBoard::pack(&board) {
+            PackedBoard::Small(key) => self.small.entry(key).or_insert(default),
+            PackedBoard::Medium(key) => self.medium.entry(key).or_insert(default),
+            PackedBoard::Large(board) => self.large.entry(board).or_insert(default),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, board: &Board64) -> Option<&V> {
+        match PackedBoard::pack(board) {
+            PackedBoard::Small(key) => self.small.get(&key),
+            PackedBoard::Medium(key) => self.medium.get(&key),
+            PackedBoard::Large(board) => self.large.get(&board),
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, board: &Board64) -> Option<&mut V> {
+        match PackedBoard::pack(board) {
+            PackedBoard::Small(key) => self.small.get_mut(&key),
+            PackedBoard::Medium(key) => self.medium.get_mut(&key),
+            PackedBoard::Large(board) => self.large.get_mut(&board),
+        }
+    }
+
+    #[inline]
+    pub fn contains_key(&self, board: &Board64) -> bool {
+        self.get(board).is_some()
+    }
+
+    /// Removes the board from the map.
+    /// Returns the value if the board was present.
+    #[inline]
+    pub fn remove(&mut self, board: &Board64) -> Option<V> {
+        match PackedBoard::pack(board) {
+            PackedBoard::Small(key) => self.small.remove(&key),
+            PackedBoard::Medium(key) => self.medium.remove(&key),
+            PackedBoard::Large(board) => self.large.remove(&board),
+        }
+    }
+
+    /// Returns the count of boards.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.small.len() + self.medium.len() + self.large.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.small.clear();
+        self.medium.clear();
+        self.large.clear();
+    }
+
+    /// Returns an iterator of the boards and the values.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Board64, &V)> + '_ {
+        let small = self
+            .small
+            .iter()
+            .map(|(&key, value)| (unpack_small(key), value));
+        let medium = self
+            .medium
+            .iter()
+            .map(|(&key, value)| (unpack_medium(key), value));
+        let large = self.large.iter().map(|(&board, value)| (board, value));
+        small.chain(medium).chain(large)
+    }
+}
+
+impl<V> Extend<(Board64, V)> for BoardMap<V> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (Board64, V)>>(&mut self, iter: I) {
+        for (board, value) in iter {
+            self.insert(board, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(Board64, V)> for BoardMap<V> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (Board64, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fxhash::FxHashSet;
+
+    use crate::prelude::*;
+
+    fn boards() -> Vec<Board64> {
+        let mut boards = vec![Board64::blank()];
+        for height in [1, 5, 6, 7, 11, 12, 13, 40, 63] {
+            let mut board = Board64::filled_up_to(height);
+            boards.push(board);
+            board.unset_at(Location::new(3, 0));
+            boards.push(board);
+            board.set_at(Location::new(9, height as i32 - 1));
+            board.unset_at(Location::new(0, height as i32 - 1));
+            boards.push(board);
+        }
+        boards.push(
+            Board64::from_str(
+                "\
+                X.........\
+                XX..XXXX.X\
+                ",
+            )
+            .unwrap(),
+        );
+        boards
+    }
+
+    #[test]
+    fn set() {
+        let boards = boards();
+        let mut set = BoardSet::new();
+        for &board in &boards {
+            assert!(!set.contains(&board));
+            assert!(set.insert(board));
+            assert!(set.contains(&board));
+            assert!(!set.insert(board));
+        }
+        assert_eq!(set.len(), boards.len());
+
+        let expected = boards.iter().copied().collect::<FxHashSet<_>>();
+        let actual = set.iter().collect::<FxHashSet<_>>();
+        assert_eq!(actual, expected);
+
+        for board in &boards {
+            assert!(set.remove(board));
+            assert!(!set.remove(board));
+        }
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn map() {
+        let boards = boards();
+        let mut map = boards
+            .iter()
+            .enumerate()
+            .map(|(index, &board)| (board, index))
+            .collect::<BoardMap<_>>();
+        assert_eq!(map.len(), boards.len());
+
+        for (index, board) in boards.iter().enumerate() {
+            assert_eq!(map.get(board), Some(&index));
+            *map.get_mut(board).unwrap() += 100;
+        }
+
+        let mut actual = map
+            .iter()
+            .map(|(board, &value)| (board, value))
+            .collect::<Vec<_>>();
+        actual.sort_by_key(|&(_, value)| value);
+        let expected = boards
+            .iter()
+            .enumerate()
+            .map(|(index, &board)| (board, index + 100))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+
+        for (index, board) in boards.iter().enumerate() {
+            assert_eq!(map.remove(board), Some(index + 100));
+            assert!(!map.contains_key(board));
+        }
+        assert!(map.is_empty());
+    }
+}
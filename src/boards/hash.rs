@@ -0,0 +1,186 @@
+use crate::boards::Board;
+use crate::pieces::Shape;
+
+/// A fast and stable 64-bit hash for the boards and the pieces, used as the keys of transposition tables.
+///
+/// Unlike `std::hash::Hash`, the value doesn't depend on the platform or the version of Rust,
+/// so it can be saved and compared across runs. It's not cryptographic.
+///
+/// The algorithm: the state starts from `0x9E3779B97F4A7C15`, and each 64-bit word is mixed as `state = mix(state + word)`,
+/// where `mix` is the finalizer of SplitMix64.
+/// A board writes its columns from left to right, each as the lower 64 bits and then the upper 64 bits only if they're not zero.
+/// Therefore, the same blocks have the same hash regardless of the height of the board (`Board8` to `Board128`).
+/// A piece writes `Shape as u64 + 1`, and no piece writes 0. A queue writes its length and then the pieces.
+/// ```
+/// use bitris::prelude::*;
+/// let board = Board64::filled_up_to(2);
+/// assert_eq!(board.hash64(), Board8::filled_up_to(2).hash64());
+/// assert_ne!(board.hash64(), Board64::filled_up_to(3).hash64());
+///
+/// // The board with the hold and the queue.
+/// let key = BoardHasher::new()
+///     .board(&board)
+///     .piece(Some(Shape::T))
+///     .queue(&[Shape::I, Shape::O])
+///     .finish();
+/// assert_ne!(key, board.hash64());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BoardHasher {
+    state: u64,
+}
+
+impl Default for BoardHasher {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BoardHasher {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: 0x9E3779B97F4A7C15,
+        }
+    }
+
+    /// Mixes a 64-bit word.
+    #[inline]
+    pub const fn word(self, word: u64) -> Self {
+        Self {
+            state: mix(self.state.wrapping_add(word)),
+        }
+    }
+
+    /// Mixes the blocks of the board.
+    #[inline]
+    pub fn board<T: Copy + Into<u128>>(self, board: &Board<T>) -> Self {
+        board.cols.iter().fold(self, |hasher, &col| {
+            let col: u128 = col.into();
+            let hasher = hasher.word(col as u64);
+            let upper = (col >> 64) as u64;
+            if upper != 0 {
+                hasher.word(upper)
+            } else {
+                hasher
+            }
+        })
+    }
+
+    /// Mixes a piece such as the hold. None is distinguished from all shapes.
+    #[inline]
+    pub const fn piece(self, piece: Option<Shape>) -> Self {
+        match piece {
+            Some(shape) => self.word(shape as u64 + 1),
+            None => self.word(0),
+        }
+    }
+
+    /// Mixes the pieces in order.
+    #[inline]
+    pub fn queue(self, queue: &[Shape]) -> Self {
+        queue
+            .iter()
+            .fold(self.word(queue.len() as u64), |hasher, &shape| {
+                hasher.piece(Some(shape))
+            })
+    }
+
+    #[inline]
+    pub const fn finish(self) -> u64 {
+        self.state
+    }
+}
+
+// The finalizer of SplitMix64.
+#[inline(always)]
+const fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+    x ^ (x >> 31)
+}
+
+impl<T: Copy + Into<u128>> Board<T> {
+    /// Returns the stable 64-bit hash of the blocks. See `BoardHasher` for the details.
+    #[inline]
+    pub fn hash64(&self) -> u64 {
+        BoardHasher::new().board(self).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fxhash::FxHashSet;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn stable_values() {
+        // The values must not change, since they can be saved.
+        assert_eq!(Board64::blank().hash64(), 0x5B29_A0AB_0E5A_EE89);
+        assert_eq!(BoardHasher::new().finish(), 0x9E37_79B9_7F4A_7C15);
+    }
+
+    #[test]
+    fn independent_of_height() {
+        let board = Board64::from_str(
+            "\
+            X.........\
+            XX..XXXX.X\
+            ",
+        )
+        .unwrap();
+        let hash = board.hash64();
+        assert_eq!(Board8::shrink_from(board).hash64(), hash);
+        assert_eq!(Board16::shrink_from(board).hash64(), hash);
+        assert_eq!(Board32::shrink_from(board).hash64(), hash);
+        assert_eq!(Board128::from(board).hash64(), hash);
+
+        let mut board = Board128::from(board);
+        board.set_at(Location::new(0, 100));
+        assert_ne!(board.hash64(), hash);
+    }
+
+    #[test]
+    fn no_collisions_in_small_boards() {
+        // All boards with 2 rows.
+        let mut hashes = FxHashSet::default();
+        for key in 0u32..(1 << 20) {
+            let mut board = Board64::blank();
+            for index in 0..20 {
+                if key & (1 << index) != 0 {
+                    board.set_at(Location::new(index % 10, index / 10));
+                }
+            }
+            assert!(hashes.insert(board.hash64()));
+        }
+    }
+
+    #[test]
+    fn pieces() {
+        let board = Board64::blank();
+        let hash = |hold, queue: &[Shape]| {
+            BoardHasher::new()
+                .board(&board)
+                .piece(hold)
+                .queue(queue)
+                .finish()
+        };
+        assert_ne!(hash(None, &[Shape::T]), hash(Some(Shape::T), &[]));
+        assert_ne!(
+            hash(None, &[Shape::T, Shape::I]),
+            hash(None, &[Shape::I, Shape::T])
+        );
+        assert_ne!(
+            hash(Some(Shape::T), &[Shape::I]),
+            hash(Some(Shape::I), &[Shape::T])
+        );
+        assert_eq!(
+            hash(Some(Shape::S), &[Shape::Z]),
+            hash(Some(Shape::S), &[Shape::Z])
+        );
+    }
+}
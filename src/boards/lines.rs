@@ -293,6 +293,165 @@ forward_ref_op! { Lines, |= Lines }
 forward_ref_op! { Lines, ^ Lines, = Lines }
 forward_ref_op! { Lines, ^= Lines }
 
+/// A key that holds a flag for each of 128 rows. This is the row key of `Board128`.
+///
+/// It works the same as `Lines`, which holds up to 64 rows.
+/// ```
+/// use bitris::prelude::*;
+/// let mut board = Board128::blank();
+/// for x in 0..10 {
+///     board.set_at(xy(x, 100));
+/// }
+/// assert_eq!(board.clear_lines(), Lines128::new_at(100));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Lines128 {
+    pub key: u128,
+}
+
+impl Lines128 {
+    #[inline]
+    pub const fn new(key: u128) -> Self {
+        Self { key }
+    }
+
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::blank(), Lines128::new(0));
+    /// ```
+    #[inline]
+    pub const fn blank() -> Self {
+        Lines128::new(0)
+    }
+
+    /// Returns a line where y is on-bit.
+    ///
+    /// Panic if y is 128 or greater.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new_at(0), Lines128::new(0b1));
+    /// assert_eq!(Lines128::new_at(127), Lines128::new(1 << 127));
+    /// ```
+    #[inline]
+    pub const fn new_at(y: u8) -> Self {
+        Lines128::new(1 << y)
+    }
+
+    /// Returns the height to the highest line. If the lines are empty, return 0.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new(0b00000).top(), 0);
+    /// assert_eq!(Lines128::new(0b10000).top(), 5);
+    /// assert_eq!(Lines128::new_at(100).top(), 101);
+    /// ```
+    #[inline]
+    pub fn top(&self) -> u32 {
+        128 - self.key.leading_zeros()
+    }
+
+    /// Count the lines that are on-bits.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new(0b11010).count(), 3);
+    /// assert_eq!(Lines128::new(u128::MAX).count(), 128);
+    /// ```
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.key.count_ones()
+    }
+
+    /// Returns whether it is on-bit or not.
+    /// Panics if `y` is over 128.
+    /// ```
+    /// use bitris::prelude::*;
+    /// let lines = Lines128::new_at(100);
+    /// assert_eq!(lines.test_at(100), true);
+    /// assert_eq!(lines.test_at(36), false);
+    /// ```
+    #[inline]
+    pub fn test_at(&self, y: usize) -> bool {
+        0 < (self.key & (1u128 << y))
+    }
+
+    /// Returns all y-coordinate of the enabled rows.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new(0b10100).ys(), vec![2, 4]);
+    /// assert_eq!(Lines128::new(1 << 127).ys(), vec![127]);
+    /// ```
+    #[inline]
+    pub fn ys(&self) -> Vec<u8> {
+        let mut vec = Vec::with_capacity(self.count() as usize);
+        let mut key = self.key;
+        while key != 0 {
+            let y = key.trailing_zeros();
+            key -= 1u128 << y;
+            vec.push(y as u8);
+        }
+        vec
+    }
+
+    /// Returns true if the enabled row does not exist.
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::blank().is_blank(), true);
+    /// assert_eq!(Lines128::new_at(100).is_blank(), false);
+    /// ```
+    #[inline]
+    pub fn is_blank(self) -> bool {
+        self.key == 0
+    }
+
+    /// Returns the rows below 64 as `Lines`.
+    /// ```
+    /// use bitris::prelude::*;
+    /// let lines = Lines128::new_at(100) | Lines128::new_at(3);
+    /// assert_eq!(lines.lower(), Lines::new_at(3));
+    /// ```
+    #[inline]
+    pub const fn lower(self) -> Lines {
+        Lines::new(self.key as u64)
+    }
+}
+
+impl From<Lines> for Lines128 {
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::from(Lines::new(0b101)), Lines128::new(0b101));
+    /// ```
+    #[inline]
+    fn from(lines: Lines) -> Self {
+        Lines128::new(lines.key as u128)
+    }
+}
+
+impl ops::BitAnd<Lines128> for Lines128 {
+    type Output = Lines128;
+
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new(0b1001) & Lines128::new(0b1010), Lines128::new(0b1000));
+    /// ```
+    fn bitand(self, rhs: Lines128) -> Self::Output {
+        Lines128::new(self.key & rhs.key)
+    }
+}
+
+impl ops::BitOr<Lines128> for Lines128 {
+    type Output = Lines128;
+
+    /// ```
+    /// use bitris::prelude::*;
+    /// assert_eq!(Lines128::new(0b1001) | Lines128::new(0b1010), Lines128::new(0b1011));
+    /// ```
+    fn bitor(self, rhs: Lines128) -> Self::Output {
+        Lines128::new(self.key | rhs.key)
+    }
+}
+
+forward_ref_op! { Lines128, & Lines128, = Lines128 }
+forward_ref_op! { Lines128, | Lines128, = Lines128 }
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -1,7 +1,13 @@
 pub use board::*;
+pub use board_set::*;
+pub use hash::*;
 pub use lines::*;
 pub use operators::*;
+pub use regions::*;
 
 mod board;
+mod board_set;
+mod hash;
 mod lines;
 mod operators;
+mod regions;
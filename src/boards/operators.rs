@@ -104,12 +104,18 @@ mod tests {
         Board64::blank()
     }
 
+    #[fixture]
+    pub fn board128() -> Board128 {
+        Board128::blank()
+    }
+
     #[template]
     #[rstest]
     #[case::board8(board8())]
     #[case::board16(board16())]
     #[case::board32(board32())]
     #[case::board64(board64())]
+    #[case::board128(board128())]
     fn all_boards(#[case] mut board: impl BoardOp) {}
 
     #[apply(all_boards)]
@@ -0,0 +1,361 @@
+use crate::boards::{Board64, BoardOp};
+use crate::pieces::Shape;
+use crate::SearchResult;
+
+#[inline]
+fn height_mask(height: u32) -> u64 {
+    if 64 <= height {
+        u64::MAX
+    } else {
+        (1u64 << height) - 1
+    }
+}
+
+/// A connected region of free cells.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FreeRegion {
+    /// The cells of the region as blocks.
+    pub cells: Board64,
+    pub count: u32,
+}
+
+impl Board64 {
+    /// Returns the connected regions of free cells under the height, from the left.
+    /// Each region is ordered by its leftmost column, and then by its lowest cell in the column.
+    /// The cells are connected up, down, left and right. The height is treated as the ceiling.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::prelude::*;
+    /// let board = Board64::from_str(
+    ///     "\
+    ///     ...X......\
+    ///     XX.XXXXX..\
+    ///     X..XXXXX..\
+    ///     ",
+    /// )
+    /// .unwrap();
+    /// let counts = board.free_regions(3).iter().map(|region| region.count).collect::<Vec<_>>();
+    /// assert_eq!(counts, vec![6, 10]);
+    ///
+    /// // The region on the left is connected to the right over the wall.
+    /// let counts = board.free_regions(4).iter().map(|region| region.count).collect::<Vec<_>>();
+    /// assert_eq!(counts, vec![26]);
+    /// ```
+    pub fn free_regions(&self, height: u32) -> Vec<FreeRegion> {
+        let mask = height_mask(height);
+        let mut remaining = self.cols.map(|col| !col & mask);
+        let mut regions = Vec::new();
+
+        while let Some(x) = remaining.iter().position(|&col| col != 0) {
+            let mut region = [0u64; 10];
+            region[x] = remaining[x] & remaining[x].wrapping_neg();
+
+            // Expands until no more cells are connected.
+            loop {
+                let mut next = region;
+                for x in 0..10 {
+                    let mut grown = region[x] | region[x] << 1 | region[x] >> 1;
+                    if 0 < x {
+                        grown |= region[x - 1];
+                    }
+                    if x < 9 {
+                        grown |= region[x + 1];
+                    }
+                    next[x] = grown & remaining[x];
+                }
+                if next == region {
+                    break;
+                }
+                region = next;
+            }
+
+            for x in 0..10 {
+                remaining[x] &= !region[x];
+            }
+            regions.push(FreeRegion {
+                cells: Board64::new(region),
+                count: region.iter().map(|col| col.count_ones()).sum(),
+            });
+        }
+
+        regions
+    }
+}
+
+/// Pruning for perfect clears, rejecting the boards that cannot be filled up to the height by the remaining pieces.
+///
+/// The filled lines are cleared before the checks, and the height is lowered by them.
+///
+/// * No blocks exist above the height, and the count of free cells is 4 times the count of the pieces.
+/// * Each connected free region has a multiple of 4 cells, since a piece cannot span regions.
+/// * The checkerboard parity: T covers 3 cells of one color and 1 of the other, and the other shapes cover 2 and 2.
+/// * The column parity: L and J cover 3 cells in even or odd columns and 1 in the others,
+///   vertical T also covers 3 and 1, vertical I covers 4 and 0, and the others cover 2 and 2.
+/// ```
+/// use std::str::FromStr;
+/// use bitris::prelude::*;
+/// let pruning = PcPruning::new(2);
+/// let board = Board64::from_str(
+///     "\
+///     XXXXXX...X\
+///     XXXXXXX.XX\
+///     ",
+/// )
+/// .unwrap();
+/// assert_eq!(pruning.validate(&board, &[Shape::T]), SearchResult::Success);
+/// assert_eq!(pruning.validate(&board, &[Shape::S]), SearchResult::Pruned);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PcPruning {
+    pub height: u32,
+}
+
+impl PcPruning {
+    #[inline]
+    pub const fn new(height: u32) -> Self {
+        Self { height }
+    }
+
+    /// Returns `SearchResult::Pruned` if the board cannot be filled up to the height by the shapes.
+    /// The order of the shapes doesn't matter.
+    pub fn validate(&self, board: &Board64, shapes: &[Shape]) -> SearchResult {
+        if self.is_fillable(board, shapes) {
+            SearchResult::Success
+        } else {
+            SearchResult::Pruned
+        }
+    }
+
+    #[inline]
+    fn is_fillable(&self, board: &Board64, shapes: &[Shape]) -> bool {
+        let mask = height_mask(self.height);
+        if board.cols.iter().any(|col| col & !mask != 0) {
+            return false;
+        }
+
+        // The pieces can span the filled lines, so they are cleared first.
+        let mut board = *board;
+        let cleared = board.clear_lines().count();
+        let pruning = Self::new(self.height.min(64) - cleared);
+
+        let free_count = 10 * pruning.height - board.count_blocks();
+        free_count == 4 * shapes.len() as u32
+            && pruning.has_fillable_regions(&board)
+            && pruning.has_fillable_parity(&board, shapes)
+    }
+
+    /// Returns true if all the free regions under the height have a multiple of 4 cells.
+    /// The filled lines are not cleared.
+    #[inline]
+    pub fn has_fillable_regions(&self, board: &Board64) -> bool {
+        board
+            .free_regions(self.height)
+            .iter()
+            .all(|region| region.count % 4 == 0)
+    }
+
+    /// Returns true if the parities of the free cells under the height can be balanced by the shapes.
+    /// The filled lines are not cleared.
+    pub fn has_fillable_parity(&self, board: &Board64, shapes: &[Shape]) -> bool {
+        let mask = height_mask(self.height);
+        let checkerboard = 0x5555_5555_5555_5555u64;
+
+        let mut checkerboard_diff = 0i32;
+        let mut column_diff = 0i32;
+        for (x, col) in board.cols.iter().enumerate() {
+            let free = !col & mask;
+            let (even, odd) = if x % 2 == 0 {
+                (free & checkerboard, free & !checkerboard)
+            } else {
+                (free & !checkerboard, free & checkerboard)
+            };
+            checkerboard_diff += even.count_ones() as i32 - odd.count_ones() as i32;
+
+            let count = free.count_ones() as i32;
+            column_diff += if x % 2 == 0 { count } else { -count };
+        }
+
+        let count_of = |targets: &[Shape]| {
+            shapes
+                .iter()
+                .filter(|shape| targets.contains(shape))
+                .count() as i32
+        };
+
+        // `fixed` pieces change the diff by ±2, `flexible` by 0 or ±2, and `fours` by 0 or ±4.
+        let is_balanced = |diff: i32, fixed: i32, flexible: i32, fours: i32| {
+            if diff % 2 != 0 || 2 * fixed + 2 * flexible + 4 * fours < diff.abs() {
+                return false;
+            }
+            0 < flexible || (diff - 2 * fixed) % 4 == 0
+        };
+
+        is_balanced(checkerboard_diff, count_of(&[Shape::T]), 0, 0)
+            && is_balanced(
+                column_diff,
+                count_of(&[Shape::L, Shape::J]),
+                count_of(&[Shape::T]),
+                count_of(&[Shape::I]),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn free_regions() {
+        assert_eq!(Board64::blank().free_regions(0), vec![]);
+
+        let regions = Board64::blank().free_regions(4);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].count, 40);
+        assert_eq!(regions[0].cells, Board64::filled_up_to(4));
+
+        let board = Board64::from_str(
+            "\
+            XXXX.XXXXX\
+            X..X.X...X\
+            XXXX.XXX.X\
+            ",
+        )
+        .unwrap();
+        let regions = board.free_regions(3);
+        assert_eq!(
+            regions.iter().map(|it| it.count).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        assert!(regions[0].cells.is_occupied_at(Location::new(1, 1)));
+        assert!(regions[2].cells.is_occupied_at(Location::new(6, 1)));
+        assert!(regions[1].cells.is_occupied_at(Location::new(4, 0)));
+
+        // Full heights are supported.
+        assert_eq!(Board64::blank().free_regions(64)[0].count, 640);
+    }
+
+    #[test]
+    fn regions_and_counts() {
+        let pruning = PcPruning::new(4);
+        let board = Board64::from_str(
+            "\
+            XXXX.XXXXX\
+            XXXX.XXXXX\
+            XXX..XXXXX\
+            XXX.XX...X\
+            ",
+        )
+        .unwrap();
+
+        // 5 and 3 cells.
+        assert!(!pruning.has_fillable_regions(&board));
+        assert_eq!(
+            pruning.validate(&board, &[Shape::L, Shape::J]),
+            SearchResult::Pruned
+        );
+
+        // The filled lines are cleared, and O spans them.
+        let board = Board64::from_str(
+            "\
+            XXXXXXXX..\
+            XXXXXXXXXX\
+            XXXXXXXXXX\
+            XXXXXXXX..\
+            ",
+        )
+        .unwrap();
+        assert!(!pruning.has_fillable_regions(&board));
+        assert_eq!(pruning.validate(&board, &[Shape::O]), SearchResult::Success);
+
+        // Blocks over the height.
+        assert_eq!(
+            PcPruning::new(1).validate(&Board64::filled_up_to(2), &[]),
+            SearchResult::Pruned
+        );
+
+        // Mismatched count of pieces.
+        let board = Board64::from_str("XXXXXX....").unwrap();
+        assert_eq!(pruning.validate(&board, &[Shape::I]), SearchResult::Pruned);
+        assert_eq!(
+            PcPruning::new(1).validate(&board, &[Shape::I]),
+            SearchResult::Success
+        );
+    }
+
+    #[test]
+    fn parity() {
+        // 2x2 hole.
+        let board = Board64::from_str(
+            "\
+            XXXXXXXX..\
+            XXXXXXXX..\
+            ",
+        )
+        .unwrap();
+        let pruning = PcPruning::new(2);
+        assert_eq!(pruning.validate(&board, &[Shape::O]), SearchResult::Success);
+        assert!(pruning.has_fillable_parity(&board, &[Shape::O]));
+        assert!(pruning.has_fillable_parity(&board, &[Shape::S]));
+
+        // 1x4 hole, the column parity needs a vertical I.
+        let board = Board64::from_str(
+            "\
+            XXXXXXXXX.\
+            XXXXXXXXX.\
+            XXXXXXXXX.\
+            XXXXXXXXX.\
+            ",
+        )
+        .unwrap();
+        let pruning = PcPruning::new(4);
+        assert!(pruning.has_fillable_parity(&board, &[Shape::I]));
+        assert!(!pruning.has_fillable_parity(&board, &[Shape::O]));
+        assert!(!pruning.has_fillable_parity(&board, &[Shape::T]));
+
+        // The checkerboard parity needs T.
+        let board = Board64::from_str(
+            "\
+            XXXXXX...X\
+            XXXXXXX.XX\
+            ",
+        )
+        .unwrap();
+        let pruning = PcPruning::new(2);
+        assert!(pruning.has_fillable_parity(&board, &[Shape::T]));
+        assert!(!pruning.has_fillable_parity(&board, &[Shape::L]));
+        assert!(!pruning.has_fillable_parity(&board, &[Shape::S]));
+
+        // Two T's cancel each other in the checkerboard parity, and one of them is vertical in the column parity.
+        let board = Board64::from_str(
+            "\
+            XXXXX...XX\
+            XXXXX...XX\
+            XXXXXXXX..\
+            XXXXXXXX..\
+            ",
+        )
+        .unwrap();
+        let pruning = PcPruning::new(4);
+        assert!(pruning.has_fillable_parity(&board, &[Shape::T, Shape::T, Shape::O]));
+        assert!(pruning.has_fillable_parity(&board, &[Shape::L, Shape::O, Shape::O]));
+        assert!(!pruning.has_fillable_parity(&board, &[Shape::L, Shape::J, Shape::O]));
+    }
+
+    #[test]
+    fn blank_four_lines() {
+        // Any 10 pieces can pass, except the odd count of T.
+        let pruning = PcPruning::new(4);
+        let board = Board64::blank();
+        assert_eq!(
+            pruning.validate(&board, &[Shape::I; 10]),
+            SearchResult::Success
+        );
+        let mut shapes = vec![Shape::T];
+        shapes.extend([Shape::O; 9]);
+        assert_eq!(pruning.validate(&board, &shapes), SearchResult::Pruned);
+        shapes[1] = Shape::T;
+        assert_eq!(pruning.validate(&board, &shapes), SearchResult::Success);
+    }
+}
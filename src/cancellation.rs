@@ -0,0 +1,186 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::SearchStatus;
+
+/// A token to stop long searches, by calling `cancel()` from anywhere or by the deadline.
+/// The clones share the cancellation, so it can be cancelled from another thread.
+/// ```
+/// use std::time::Duration;
+/// use bitris::prelude::*;
+///
+/// let token = CancelToken::with_timeout(Duration::from_secs(60));
+/// assert_eq!(token.status(), SearchStatus::Completed);
+///
+/// token.clone().cancel();
+/// assert!(token.is_cancelled());
+/// assert_eq!(token.status(), SearchStatus::Cancelled);
+///
+/// let token = CancelToken::with_timeout(Duration::ZERO);
+/// assert!(!token.is_cancelled());
+/// assert_eq!(token.status(), SearchStatus::TimedOut);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    /// Makes the token without the deadline. It stops searches only by `cancel()`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_deadline(deadline: Instant) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            deadline: Some(deadline),
+        }
+    }
+
+    /// Makes the token with the deadline after the timeout from now.
+    #[inline]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_deadline(Instant::now() + timeout)
+    }
+
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Stops the searches using this token or its clones.
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the status if searches stop now: `Cancelled` or `TimedOut` if they should stop, otherwise `Completed`.
+    /// The cancellation takes precedence over the deadline.
+    #[inline]
+    pub fn status(&self) -> SearchStatus {
+        if self.is_cancelled() {
+            SearchStatus::Cancelled
+        } else if self.is_expired() {
+            SearchStatus::TimedOut
+        } else {
+            SearchStatus::Completed
+        }
+    }
+
+    /// Returns true if `cancel()` has been called. The deadline is not included, see `status()` for it.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| deadline <= Instant::now())
+    }
+}
+
+/// The count of calls to `CancelWatch::should_stop()` per reading the clock.
+const CLOCK_INTERVAL: u32 = 256;
+
+/// Watches the token in a search, and keeps the status at the first stop.
+/// The status is kept so that a search finished before the deadline is not reported as timed out.
+///
+/// The flag of `cancel()` is read on every check, but the clock is read only on the first check and every `CLOCK_INTERVAL` checks,
+/// since the checks are made on each node of the searches.
+pub(crate) struct CancelWatch<'a> {
+    token: &'a CancelToken,
+    status: Cell<SearchStatus>,
+    calls: Cell<u32>,
+}
+
+impl<'a> CancelWatch<'a> {
+    #[inline]
+    pub(crate) fn new(token: &'a CancelToken) -> Self {
+        Self {
+            token,
+            status: Cell::new(SearchStatus::Completed),
+            calls: Cell::new(0),
+        }
+    }
+
+    /// Returns true if the search should stop.
+    #[inline]
+    pub(crate) fn should_stop(&self) -> bool {
+        if !self.status.get().is_completed() {
+            return true;
+        }
+        let calls = self.calls.get();
+        self.calls.set((calls + 1) % CLOCK_INTERVAL);
+
+        let status = if self.token.is_cancelled() {
+            SearchStatus::Cancelled
+        } else if calls == 0 && self.token.is_expired() {
+            SearchStatus::TimedOut
+        } else {
+            SearchStatus::Completed
+        };
+        self.status.set(status);
+        !status.is_completed()
+    }
+
+    #[inline]
+    pub(crate) fn status(&self) -> SearchStatus {
+        self.status.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::cancellation::{CancelWatch, CLOCK_INTERVAL};
+    use crate::prelude::*;
+
+    #[test]
+    fn watch_keeps_first_stop() {
+        // The deadline has passed, but the watch doesn't read the clock until the next check.
+        let token = CancelToken::with_deadline(Instant::now());
+        let watch = CancelWatch::new(&token);
+        assert_eq!(watch.status(), SearchStatus::Completed);
+
+        // The clock is read on the first check and then once per the interval.
+        watch.calls.set(1);
+        let checks = (0..CLOCK_INTERVAL)
+            .position(|_| watch.should_stop())
+            .unwrap();
+        assert_eq!(checks, CLOCK_INTERVAL as usize - 1);
+        assert_eq!(watch.status(), SearchStatus::TimedOut);
+
+        token.cancel();
+        assert!(watch.should_stop());
+        assert_eq!(watch.status(), SearchStatus::TimedOut);
+        assert_eq!(token.status(), SearchStatus::Cancelled);
+    }
+
+    #[test]
+    fn watch_reads_clock_on_first_check() {
+        let token = CancelToken::with_timeout(Duration::ZERO);
+        let watch = CancelWatch::new(&token);
+        assert!(watch.should_stop());
+        assert_eq!(watch.status(), SearchStatus::TimedOut);
+    }
+
+    #[test]
+    fn watch_reads_flag_every_check() {
+        let token = CancelToken::with_timeout(Duration::from_secs(60));
+        let watch = CancelWatch::new(&token);
+        assert!(!watch.should_stop());
+        assert!(!watch.should_stop());
+
+        token.cancel();
+        assert!(watch.should_stop());
+        assert_eq!(watch.status(), SearchStatus::Cancelled);
+    }
+}
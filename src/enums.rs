@@ -13,3 +13,20 @@ pub enum SearchResult {
     Success,
     Pruned,
 }
+
+/// A collection of statuses indicating how a search ended.
+/// The results found before the interruption are still valid, but they may be partial.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug)]
+pub enum SearchStatus {
+    #[default]
+    Completed,
+    Cancelled,
+    TimedOut,
+}
+
+impl SearchStatus {
+    #[inline]
+    pub fn is_completed(self) -> bool {
+        self == SearchStatus::Completed
+    }
+}
@@ -0,0 +1,317 @@
+use crate::boards::BoardOp;
+use crate::coordinates::{bl, BlPosition};
+use crate::internal_macros::enum_display;
+use crate::pieces::{Orientation, Shape};
+use crate::placements::BlPlacement;
+use crate::With;
+
+/// A collection of conditions that end the game.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum TopOut {
+    /// The next piece cannot spawn because blocks are in the way.
+    BlockOut,
+    /// The piece was locked above the visible field.
+    LockOut,
+}
+
+enum_display! { TopOut, has BlockOut,LockOut }
+
+/// A collection of conditions under which a lock is a lock-out.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum LockOutRule {
+    /// Lock-out never happens.
+    Disabled,
+    /// Lock-out happens when all blocks of the piece are above the visible field.
+    #[default]
+    Entire,
+    /// Lock-out happens when any block of the piece is above the visible field.
+    Partial,
+}
+
+enum_display! { LockOutRule, has Disabled,Entire,Partial }
+
+/// Rules about the spawn and the end of the game.
+///
+/// Use it to check the spawn before move generation, which panics if the spawn is not placeable.
+/// ```
+/// use std::str::FromStr;
+/// use bitris::prelude::*;
+/// let rules = GameRules::guideline();
+/// let board = Board64::from_str("
+///     ...###....
+///     ..........
+/// ").unwrap();
+///
+/// let spawn = rules.try_spawn(&board, Shape::T).unwrap();
+/// assert_eq!(spawn, Shape::T.with(Orientation::North).with(bl(3, 20)));
+/// let moves = MoveRules::default().generate_minimized_moves(board, spawn);
+/// assert!(!moves.is_empty());
+///
+/// let board = Board64::filled_up_to(21);
+/// assert_eq!(rules.try_spawn(&board, Shape::T), Err(TopOut::BlockOut));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct GameRules {
+    /// The spawn positions indexed by `Shape as usize`.
+    pub spawn_positions: [BlPosition; 7],
+    pub spawn_orientation: Orientation,
+    /// The number of rows from the bottom that are visible. Rows above it are the buffer zone.
+    pub visible_height: u32,
+    pub lock_out: LockOutRule,
+}
+
+impl Default for GameRules {
+    #[inline]
+    fn default() -> Self {
+        Self::guideline()
+    }
+}
+
+impl GameRules {
+    #[inline]
+    pub const fn new(
+        spawn_positions: [BlPosition; 7],
+        spawn_orientation: Orientation,
+        visible_height: u32,
+        lock_out: LockOutRule,
+    ) -> Self {
+        Self {
+            spawn_positions,
+            spawn_orientation,
+            visible_height,
+            lock_out,
+        }
+    }
+
+    /// The rules by the guideline.
+    /// The pieces spawn in the north orientation just above the 20 visible rows, at the center (left-biased).
+    /// Locking the piece entirely above the visible field is a lock-out.
+    #[inline]
+    pub const fn guideline() -> Self {
+        Self::new(
+            [
+                bl(3, 20), // T
+                bl(3, 20), // I
+                bl(4, 20), // O
+                bl(3, 20), // L
+                bl(3, 20), // J
+                bl(3, 20), // S
+                bl(3, 20), // Z
+            ],
+            Orientation::North,
+            20,
+            LockOutRule::Entire,
+        )
+    }
+
+    /// The rules as in NES Tetris, used with `MoveRules::nrs()`.
+    /// The pieces spawn in the top two visible rows, and there's no buffer zone above the 20 rows.
+    /// T, L and J spawn flat side up, which is the south orientation.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = GameRules::nes();
+    /// assert_eq!(rules.spawn(Shape::T), piece!(TS).with(bl(4, 18)));
+    /// assert_eq!(rules.spawn(Shape::I), piece!(IS).with(bl(3, 19)));
+    /// ```
+    #[inline]
+    pub const fn nes() -> Self {
+        Self::new(
+            [
+                bl(4, 18), // T
+                bl(3, 19), // I
+                bl(4, 18), // O
+                bl(4, 18), // L
+                bl(4, 18), // J
+                bl(4, 18), // S
+                bl(4, 18), // Z
+            ],
+            Orientation::South,
+            20,
+            LockOutRule::Disabled,
+        )
+    }
+
+    /// Returns the spawn placement of the shape. Whether the piece can spawn on the board is not checked.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = GameRules::guideline();
+    /// assert_eq!(rules.spawn(Shape::O), piece!(ON).with(bl(4, 20)));
+    /// ```
+    #[inline]
+    pub fn spawn(&self, shape: Shape) -> BlPlacement {
+        shape
+            .with(self.spawn_orientation)
+            .with(self.spawn_positions[shape as usize])
+    }
+
+    /// Returns the spawn placement of the shape if it can spawn on the board.
+    /// Returns `TopOut::BlockOut` if the spawn overlaps blocks or is outside the board.
+    #[inline]
+    pub fn try_spawn(&self, board: &impl BoardOp, shape: Shape) -> Result<BlPlacement, TopOut> {
+        let spawn = self.spawn(shape);
+        if spawn.is_in_free_space(board) {
+            Ok(spawn)
+        } else {
+            Err(TopOut::BlockOut)
+        }
+    }
+
+    /// Returns true if the piece can spawn on the board.
+    #[inline]
+    pub fn can_spawn(&self, board: &impl BoardOp, shape: Shape) -> bool {
+        self.try_spawn(board, shape).is_ok()
+    }
+
+    /// Returns true if locking the placement is a lock-out.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = GameRules::guideline();
+    /// assert!(!rules.is_lock_out(piece!(TN).with(bl(3, 19))));
+    /// assert!(rules.is_lock_out(piece!(TN).with(bl(3, 20))));
+    /// ```
+    #[inline]
+    pub fn is_lock_out(&self, placement: BlPlacement) -> bool {
+        let visible_height = self.visible_height as i32;
+        let locations = placement.locations();
+        match self.lock_out {
+            LockOutRule::Disabled => false,
+            LockOutRule::Entire => locations.iter().all(|it| visible_height <= it.y),
+            LockOutRule::Partial => locations.iter().any(|it| visible_height <= it.y),
+        }
+    }
+
+    /// Checks the end of the game after the placement was locked and lines were cleared.
+    /// The board must be the one after the lock.
+    ///
+    /// Returns `TopOut::LockOut` if the lock is a lock-out, and `TopOut::BlockOut` if the next piece cannot spawn.
+    /// Returns None if the game can continue.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = GameRules::guideline();
+    ///
+    /// let mut board = Board64::blank();
+    /// let placement = piece!(IE).with(bl(0, 0));
+    /// placement.place_on_and_clear_lines(&mut board).unwrap();
+    /// assert_eq!(rules.check_top_out(&board, placement, Shape::T), None);
+    ///
+    /// let placement = piece!(ON).with(bl(0, 20));
+    /// assert_eq!(rules.check_top_out(&board, placement, Shape::T), Some(TopOut::LockOut));
+    /// ```
+    #[inline]
+    pub fn check_top_out(
+        &self,
+        board: &impl BoardOp,
+        locked: BlPlacement,
+        next: Shape,
+    ) -> Option<TopOut> {
+        if self.is_lock_out(locked) {
+            return Some(TopOut::LockOut);
+        }
+        self.try_spawn(board, next).err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::piece;
+    use crate::prelude::*;
+
+    #[test]
+    fn guideline_spawns() {
+        let rules = GameRules::guideline();
+        let board = Board64::filled_up_to(20);
+        for shape in Shape::all_iter() {
+            let spawn = rules.spawn(shape);
+            assert_eq!(spawn.piece.orientation, Orientation::North);
+            assert!(spawn.locations().iter().all(|location| 20 <= location.y));
+            assert_eq!(rules.try_spawn(&board, shape), Ok(spawn));
+        }
+    }
+
+    #[test]
+    fn nes_spawns() {
+        let rules = GameRules::nes();
+        let board = Board64::filled_up_to(18);
+        for shape in Shape::all_iter() {
+            let spawn = rules.spawn(shape);
+            assert!(spawn
+                .locations()
+                .iter()
+                .all(|location| (18..20).contains(&location.y)));
+            assert_eq!(rules.try_spawn(&board, shape), Ok(spawn));
+        }
+        assert!(!rules.can_spawn(&Board64::filled_up_to(19), Shape::T));
+    }
+
+    #[test]
+    fn block_out() {
+        let rules = GameRules::guideline();
+        let mut board = Board64::blank();
+        board.set_at(Location::new(5, 21));
+
+        assert_eq!(
+            rules.try_spawn(&board, Shape::T),
+            Ok(piece!(TN).with(bl(3, 20)))
+        );
+        assert_eq!(rules.try_spawn(&board, Shape::O), Err(TopOut::BlockOut));
+        assert_eq!(rules.try_spawn(&board, Shape::S), Err(TopOut::BlockOut));
+        assert!(rules.can_spawn(&board, Shape::I));
+
+        // Outside the board is treated as blocks.
+        assert_eq!(
+            rules.try_spawn(&Board32::blank(), Shape::T),
+            Ok(piece!(TN).with(bl(3, 20)))
+        );
+        assert_eq!(
+            rules.try_spawn(&Board16::blank(), Shape::T),
+            Err(TopOut::BlockOut)
+        );
+    }
+
+    #[test]
+    fn lock_out() {
+        let mut rules = GameRules::guideline();
+        let above = piece!(IN).with(bl(0, 20));
+        let across = piece!(IE).with(bl(0, 18));
+        let below = piece!(IE).with(bl(0, 16));
+
+        assert!(rules.is_lock_out(above));
+        assert!(!rules.is_lock_out(across));
+        assert!(!rules.is_lock_out(below));
+
+        rules.lock_out = LockOutRule::Partial;
+        assert!(rules.is_lock_out(above));
+        assert!(rules.is_lock_out(across));
+        assert!(!rules.is_lock_out(below));
+
+        rules.lock_out = LockOutRule::Disabled;
+        assert!(!rules.is_lock_out(above));
+        assert!(!rules.is_lock_out(across));
+        assert!(!rules.is_lock_out(below));
+    }
+
+    #[test]
+    fn check_top_out() {
+        let rules = GameRules::guideline();
+
+        let board = Board64::blank();
+        assert_eq!(
+            rules.check_top_out(&board, piece!(IN).with(bl(0, 20)), Shape::T),
+            Some(TopOut::LockOut)
+        );
+        assert_eq!(
+            rules.check_top_out(&board, piece!(IN).with(bl(0, 19)), Shape::T),
+            None
+        );
+
+        let board = Board64::filled_up_to(21);
+        assert_eq!(
+            rules.check_top_out(&board, piece!(IN).with(bl(0, 19)), Shape::T),
+            Some(TopOut::BlockOut)
+        );
+    }
+}
@@ -1,9 +1,13 @@
-/** It's auto generated. */
-use crate::internal_moves::u64::free_space::FreeSpace64;
+/*! It's auto generated. */
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::MoveBits;
 use crate::pieces::{Orientation, Piece, Shape};
 
 #[inline(always)]
-pub fn to_free_spaces(free_space_block: FreeSpace64, shape: Shape) -> [FreeSpace64; 4] {
+pub fn to_free_spaces<T: MoveBits>(
+    free_space_block: FreeSpace<T>,
+    shape: Shape,
+) -> [FreeSpace<T>; 4] {
     match shape {
         Shape::T => [
             t_north(free_space_block.clone()),
@@ -51,7 +55,7 @@ pub fn to_free_spaces(free_space_block: FreeSpace64, shape: Shape) -> [FreeSpace
 }
 
 #[inline(always)]
-pub fn to_free_space(free_space_block: FreeSpace64, piece: Piece) -> FreeSpace64 {
+pub fn to_free_space<T: MoveBits>(free_space_block: FreeSpace<T>, piece: Piece) -> FreeSpace<T> {
     match piece.shape {
         Shape::T => match piece.orientation {
             Orientation::North => t_north(free_space_block.clone()),
@@ -99,7 +103,7 @@ pub fn to_free_space(free_space_block: FreeSpace64, piece: Piece) -> FreeSpace64
 }
 
 #[inline(always)]
-fn t_north(space: FreeSpace64) -> FreeSpace64 {
+fn t_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 0>()
@@ -109,7 +113,7 @@ fn t_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn t_east(space: FreeSpace64) -> FreeSpace64 {
+fn t_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 1, 0>()
@@ -119,7 +123,7 @@ fn t_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn t_south(space: FreeSpace64) -> FreeSpace64 {
+fn t_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 0>()
@@ -129,7 +133,7 @@ fn t_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn t_west(space: FreeSpace64) -> FreeSpace64 {
+fn t_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 0, 1>()
@@ -139,7 +143,7 @@ fn t_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn i_north(space: FreeSpace64) -> FreeSpace64 {
+fn i_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 0>()
@@ -149,7 +153,7 @@ fn i_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn i_east(space: FreeSpace64) -> FreeSpace64 {
+fn i_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 1, 0>()
@@ -159,7 +163,7 @@ fn i_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn i_south(space: FreeSpace64) -> FreeSpace64 {
+fn i_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 0>()
@@ -169,7 +173,7 @@ fn i_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn i_west(space: FreeSpace64) -> FreeSpace64 {
+fn i_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 0, 1>()
@@ -179,7 +183,7 @@ fn i_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn o_north(space: FreeSpace64) -> FreeSpace64 {
+fn o_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .and(space.clone().shift::<1, 0, 0, 0>())
@@ -188,7 +192,7 @@ fn o_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn o_east(space: FreeSpace64) -> FreeSpace64 {
+fn o_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .and(space.clone().shift::<0, 0, 0, 1>())
@@ -197,7 +201,7 @@ fn o_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn o_south(space: FreeSpace64) -> FreeSpace64 {
+fn o_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .and(space.clone().shift::<0, 1, 0, 0>())
@@ -206,7 +210,7 @@ fn o_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn o_west(space: FreeSpace64) -> FreeSpace64 {
+fn o_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .and(space.clone().shift::<0, 0, 1, 0>())
@@ -215,7 +219,7 @@ fn o_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn l_north(space: FreeSpace64) -> FreeSpace64 {
+fn l_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 0>()
@@ -225,7 +229,7 @@ fn l_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn l_east(space: FreeSpace64) -> FreeSpace64 {
+fn l_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 1, 0>()
@@ -235,7 +239,7 @@ fn l_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn l_south(space: FreeSpace64) -> FreeSpace64 {
+fn l_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 0>()
@@ -245,7 +249,7 @@ fn l_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn l_west(space: FreeSpace64) -> FreeSpace64 {
+fn l_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 0, 1>()
@@ -255,7 +259,7 @@ fn l_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn j_north(space: FreeSpace64) -> FreeSpace64 {
+fn j_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 1, 0>()
@@ -265,7 +269,7 @@ fn j_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn j_east(space: FreeSpace64) -> FreeSpace64 {
+fn j_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 1, 0>()
@@ -275,7 +279,7 @@ fn j_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn j_south(space: FreeSpace64) -> FreeSpace64 {
+fn j_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 1>()
@@ -285,7 +289,7 @@ fn j_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn j_west(space: FreeSpace64) -> FreeSpace64 {
+fn j_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 1>()
@@ -295,7 +299,7 @@ fn j_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn s_north(space: FreeSpace64) -> FreeSpace64 {
+fn s_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 0>()
@@ -305,7 +309,7 @@ fn s_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn s_east(space: FreeSpace64) -> FreeSpace64 {
+fn s_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 1, 0>()
@@ -315,7 +319,7 @@ fn s_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn s_south(space: FreeSpace64) -> FreeSpace64 {
+fn s_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 0>()
@@ -325,7 +329,7 @@ fn s_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn s_west(space: FreeSpace64) -> FreeSpace64 {
+fn s_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 0, 0, 1>()
@@ -335,7 +339,7 @@ fn s_west(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn z_north(space: FreeSpace64) -> FreeSpace64 {
+fn z_north<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 1, 0>()
@@ -345,7 +349,7 @@ fn z_north(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn z_east(space: FreeSpace64) -> FreeSpace64 {
+fn z_east<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 1, 0>()
@@ -355,7 +359,7 @@ fn z_east(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn z_south(space: FreeSpace64) -> FreeSpace64 {
+fn z_south<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<1, 0, 0, 1>()
@@ -365,7 +369,7 @@ fn z_south(space: FreeSpace64) -> FreeSpace64 {
 }
 
 #[inline(always)]
-fn z_west(space: FreeSpace64) -> FreeSpace64 {
+fn z_west<T: MoveBits>(space: FreeSpace<T>) -> FreeSpace<T> {
     space
         .clone()
         .shift::<0, 1, 0, 1>()
@@ -1,22 +1,22 @@
-use crate::boards::Board64;
-use crate::internal_moves::u64::opu64;
+use crate::boards::Board;
+use crate::internal_moves::{ops, MoveBits};
 use crate::prelude::Location;
 
 // The position where there is space to place a piece is represented by 1.
 // The flags are aggregated to the position that corresponds to Bottom-Left.
 #[derive(Debug, Clone)]
-pub struct FreeSpace64 {
-    pub cols: [u64; 10],
+pub struct FreeSpace<T: MoveBits> {
+    pub cols: [T; 10],
 }
 
-impl FreeSpace64 {
+impl<T: MoveBits> FreeSpace<T> {
     #[inline(always)]
-    pub fn new(cols: [u64; 10]) -> Self {
+    pub fn new(cols: [T; 10]) -> Self {
         Self { cols }
     }
 
     #[inline(always)]
-    pub fn and(self, other: FreeSpace64) -> Self {
+    pub fn and(self, other: FreeSpace<T>) -> Self {
         let mut cols = self.cols;
         for index in 0..self.cols.len() {
             cols[index] &= other.cols[index];
@@ -26,17 +26,17 @@ impl FreeSpace64 {
 
     #[inline(always)]
     pub fn shift<const LEFT: i32, const RIGHT: i32, const DOWN: i32, const UP: i32>(self) -> Self {
-        Self::new(opu64::shift::<LEFT, RIGHT, DOWN, UP>(self.cols))
+        Self::new(ops::shift::<T, LEFT, RIGHT, DOWN, UP>(self.cols))
     }
 
     #[inline(always)]
     pub fn is_free_at(&self, location: Location) -> bool {
-        self.cols[location.x as usize] & (1u64 << location.y as usize) != 0
+        self.cols[location.x as usize] & (T::ONE << location.y as u32) != T::ZERO
     }
 }
 
-impl From<&FreeSpace64> for Board64 {
-    fn from(value: &FreeSpace64) -> Self {
-        Board64::new(value.cols)
+impl<T: MoveBits> From<&FreeSpace<T>> for Board<T> {
+    fn from(value: &FreeSpace<T>) -> Self {
+        Board { cols: value.cols }
     }
 }
@@ -1,28 +1,31 @@
 use crate::array_map::zip2_map4;
 use crate::boards::Board;
-use crate::internal_moves::u64::loaders::{
+use crate::internal_moves::loaders::{
     can_reach1, can_reach4, spawn_and_harddrop_reachable, spawn_and_harddrop_reachables,
-    to_free_space, to_free_spaces,
+    to_free_space, to_free_spaces, FreeSpaceCache,
 };
-use crate::internal_moves::u64::minimize::minimize;
-use crate::internal_moves::u64::moves::{Moves1, Moves4};
+use crate::internal_moves::minimize::minimize;
+use crate::internal_moves::moves::{Moves1, Moves4};
+use crate::internal_moves::MoveBits;
 use crate::pieces::ToCcPosition;
 use crate::placements::BlPlacement;
 use crate::{RotationSystem, With};
 
-pub fn moves_harddrop_with_rotation<const MINIMIZE: bool>(
+pub fn moves_harddrop_with_rotation<T: MoveBits, const MINIMIZE: bool>(
     rotation_system: &impl RotationSystem,
-    board: &Board<u64>,
+    cache: &FreeSpaceCache<T>,
     spawn: BlPlacement,
-) -> Moves4 {
+) -> Moves4<T> {
     let spawn = spawn.to_cc_placement();
-    let free_spaces = to_free_spaces(board, spawn.piece.shape);
-    let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, &free_spaces);
+    let free_spaces = cache.free_spaces(spawn.piece.shape);
+    let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, free_spaces);
 
     // landed
-    let reachables = zip2_map4(reachables, free_spaces, |reachable, free_space| {
-        reachable.land(&free_space)
-    });
+    let reachables = zip2_map4(
+        reachables,
+        free_spaces.each_ref(),
+        |reachable, free_space| reachable.land(free_space),
+    );
 
     let reachables = if MINIMIZE {
         minimize(reachables, spawn.piece.shape)
@@ -36,15 +39,15 @@ pub fn moves_harddrop_with_rotation<const MINIMIZE: bool>(
     }
 }
 
-pub fn moves_harddrop_no_rotation<const MINIMIZE: bool>(
-    board: &Board<u64>,
+pub fn moves_harddrop_no_rotation<T: MoveBits, const MINIMIZE: bool>(
+    cache: &FreeSpaceCache<T>,
     spawn: BlPlacement,
-) -> Moves1 {
+) -> Moves1<T> {
     let spawn = spawn.canonical_or_self().to_cc_placement();
-    let free_space = to_free_space(board, spawn.piece);
-    let reachable = spawn_and_harddrop_reachable(spawn, &free_space);
+    let free_space = cache.free_space(spawn.piece);
+    let reachable = spawn_and_harddrop_reachable(spawn, free_space);
 
-    let reachable = reachable.land(&free_space);
+    let reachable = reachable.land(free_space);
 
     Moves1 {
         spawn_piece: spawn.piece,
@@ -53,10 +56,10 @@ pub fn moves_harddrop_no_rotation<const MINIMIZE: bool>(
     }
 }
 
-pub(crate) fn can_reach_harddrop_with_rotation(
+pub(crate) fn can_reach_harddrop_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.to_cc_placement();
@@ -74,10 +77,10 @@ pub(crate) fn can_reach_harddrop_with_rotation(
     can_reach4(&reachables, &goals)
 }
 
-pub(crate) fn can_reach_strictly_harddrop_with_rotation(
+pub(crate) fn can_reach_strictly_harddrop_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.to_cc_placement();
@@ -89,13 +92,13 @@ pub(crate) fn can_reach_strictly_harddrop_with_rotation(
     can_reach4(&reachables, &goals)
 }
 
-pub(crate) fn can_reach_harddrop_no_rotation(
+pub(crate) fn can_reach_harddrop_no_rotation<T: MoveBits>(
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.canonical_or_self().to_cc_placement();
-    let goal = goal.to_cc_placement();
+    let goal = goal.canonical_or_self().to_cc_placement();
 
     let free_space = to_free_space(board, spawn.piece);
     let reachable = spawn_and_harddrop_reachable(spawn, &free_space);
@@ -103,9 +106,9 @@ pub(crate) fn can_reach_harddrop_no_rotation(
     can_reach1(&reachable, goal)
 }
 
-pub(crate) fn can_reach_strictly_harddrop_no_rotation(
+pub(crate) fn can_reach_strictly_harddrop_no_rotation<T: MoveBits>(
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     can_reach_harddrop_no_rotation(goal, board, spawn)
@@ -0,0 +1,300 @@
+use std::cell::OnceCell;
+
+use crate::array_map::map_indexed4;
+use crate::boards::Board;
+use crate::coordinates::{cc, Location, Offset};
+use crate::internal_moves::free;
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::MoveBits;
+use crate::pieces::{Piece, PieceBlocksFactory, Shape};
+use crate::prelude::{BlPlacement, CcPlacement};
+use crate::{Kick, Rotate, Rotation, RotationSystem, With};
+
+// ブロックと空を反転して読み込み
+#[inline(always)]
+pub fn to_free_space_block<T: MoveBits>(board: &Board<T>) -> FreeSpace<T> {
+    FreeSpace::new(board.cols.map(|col| !col))
+}
+
+// ブロックの位置がすべて盤面内の空きにあるか
+#[inline(always)]
+pub fn is_in_free_space_block<T: MoveBits>(
+    free_space_block: &FreeSpace<T>,
+    placement: BlPlacement,
+) -> bool {
+    placement
+        .locations()
+        .into_iter()
+        .all(|location| is_free_in_block(free_space_block, location))
+}
+
+// ブロックの位置が盤面内の空きにあるか
+#[inline(always)]
+pub fn is_free_in_block<T: MoveBits>(free_space_block: &FreeSpace<T>, location: Location) -> bool {
+    (0..10).contains(&location.x)
+        && (0..T::BITS as i32).contains(&location.y)
+        && free_space_block.is_free_at(location)
+}
+
+// 同じ盤面で共有する前処理。形ごとの空きは最初に使うときに計算する
+pub struct FreeSpaceCache<T: MoveBits> {
+    pub free_space_block: FreeSpace<T>,
+    free_spaces: [OnceCell<[FreeSpace<T>; 4]>; 7],
+}
+
+impl<T: MoveBits> FreeSpaceCache<T> {
+    #[inline(always)]
+    pub fn new(board: &Board<T>) -> Self {
+        Self {
+            free_space_block: to_free_space_block(board),
+            free_spaces: Default::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn free_spaces(&self, shape: Shape) -> &[FreeSpace<T>; 4] {
+        self.free_spaces[shape as usize]
+            .get_or_init(|| free::to_free_spaces(self.free_space_block.clone(), shape))
+    }
+
+    #[inline(always)]
+    pub fn free_space(&self, piece: Piece) -> &FreeSpace<T> {
+        &self.free_spaces(piece.shape)[piece.orientation as usize]
+    }
+}
+
+#[inline(always)]
+pub fn to_free_spaces<T: MoveBits>(board: &Board<T>, shape: Shape) -> [FreeSpace<T>; 4] {
+    free::to_free_spaces(to_free_space_block(board), shape)
+}
+
+#[inline(always)]
+pub fn to_free_space<T: MoveBits>(board: &Board<T>, piece: Piece) -> FreeSpace<T> {
+    free::to_free_space(to_free_space_block(board), piece)
+}
+
+// 回転システムの中心から、内部で使う中心への差分
+#[inline(always)]
+fn center_offset(rotation_system: &impl RotationSystem, piece: Piece) -> Offset {
+    let piece_blocks = rotation_system.piece_blocks(piece);
+    let default_piece_blocks = PieceBlocksFactory.get(piece);
+    debug_assert!(piece_blocks.has_same_form_as(default_piece_blocks));
+    piece_blocks.bottom_left - default_piece_blocks.bottom_left
+}
+
+// 内部で使う中心を基準にしたキック
+#[inline(always)]
+pub fn iter_kicks<'a>(
+    rotation_system: &'a impl RotationSystem,
+    piece: Piece,
+    rotation: Rotation,
+) -> impl Iterator<Item = Kick> + 'a {
+    let shift = center_offset(rotation_system, piece.rotate(rotation))
+        - center_offset(rotation_system, piece);
+    rotation_system
+        .iter_kicks(piece, rotation)
+        .map(move |kick| Kick::new(kick.offset + shift))
+}
+
+// 内部で使う中心を基準にしてキックをテストする
+#[inline(always)]
+pub fn test_kick(
+    rotation_system: &impl RotationSystem,
+    is_free: impl Fn(Location) -> bool,
+    placement: CcPlacement,
+    rotation: Rotation,
+) -> Option<CcPlacement> {
+    let from = placement + -center_offset(rotation_system, placement.piece);
+    rotation_system
+        .test_kick_with(is_free, from, rotation)
+        .map(|result| {
+            let destination = result.destination;
+            destination + center_offset(rotation_system, destination.piece)
+        })
+}
+
+#[inline(always)]
+pub fn spawn_and_harddrop_reachables<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    spawn: CcPlacement,
+    free_spaces: &[FreeSpace<T>; 4],
+) -> [Reachable<T>; 4] {
+    let mut placements = [None; 4];
+
+    // spawn
+    placements[spawn.piece.orientation as usize] = Some(spawn);
+
+    // rotate
+    for rotation in [Rotation::Cw, Rotation::Ccw] {
+        let mut prev = spawn;
+        for _ in 0..3 {
+            let current_piece = prev.piece.rotate(rotation);
+            let orientation_index = current_piece.orientation as usize;
+
+            if placements[orientation_index].is_some() {
+                break;
+            }
+
+            // use first kick
+            let offset = iter_kicks(rotation_system, prev.piece, rotation)
+                .next()
+                .unwrap()
+                .offset;
+            let current_position = prev.position + offset;
+            if !free_spaces[orientation_index].is_free_at(current_position.to_location()) {
+                break;
+            }
+
+            let current = current_piece.with(current_position);
+            placements[orientation_index] = Some(current);
+            prev = current;
+        }
+    }
+
+    map_indexed4(placements, |index, placement| {
+        placement
+            .map(|p| spawn_and_harddrop_reachable(p, &free_spaces[index]))
+            .unwrap_or_else(Reachable::blank)
+    })
+}
+
+#[inline(always)]
+pub fn spawn_and_harddrop_reachable<T: MoveBits>(
+    spawn: CcPlacement,
+    free_space: &FreeSpace<T>,
+) -> Reachable<T> {
+    // index
+    let spawn_location = spawn.position.to_location();
+    let spawn_x = spawn_location.x as usize;
+
+    // boards
+    let mut spawn_reachable = Reachable::blank();
+    let spawn_free_space = free_space.cols;
+
+    // a spawn bit
+    let spawn_bit = T::ONE << spawn_location.y as u32;
+
+    // 1-mask over spawn y
+    let mask = !(spawn_bit | (spawn_bit - T::ONE));
+
+    // left
+    for (x, free_space) in spawn_free_space.iter().enumerate().take(spawn_x).rev() {
+        if (spawn_bit & *free_space) == T::ZERO {
+            break;
+        }
+
+        // harddrop
+        let harddrop_dest_y = T::BITS - (!(*free_space | mask)).leading_zeros();
+        if harddrop_dest_y < spawn_location.y as u32 {
+            let reachable = (spawn_bit - T::ONE) - ((T::ONE << harddrop_dest_y) - T::ONE);
+            spawn_reachable.cols[x] = spawn_bit | reachable;
+        }
+    }
+
+    // right
+    for (x, free_space) in spawn_free_space.iter().enumerate().skip(spawn_x) {
+        if (spawn_bit & *free_space) == T::ZERO {
+            break;
+        }
+
+        // harddrop
+        let harddrop_dest_y = T::BITS - (!(*free_space | mask)).leading_zeros();
+        if harddrop_dest_y < spawn_location.y as u32 {
+            let reachable = (spawn_bit - T::ONE) - ((T::ONE << harddrop_dest_y) - T::ONE);
+            spawn_reachable.cols[x] = spawn_bit | reachable;
+        }
+    }
+    spawn_reachable
+}
+
+#[inline(always)]
+pub fn rotate<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    rotation: Rotation,
+    from_piece: Piece,
+    src_reachable: &Reachable<T>,
+    dest_free_space: &FreeSpace<T>,
+    free_space_block: &FreeSpace<T>,
+) -> Reachable<T> {
+    debug_assert!(!src_reachable.empty());
+
+    if !rotation_system.has_static_kicks() {
+        return rotate_by_test_kick(
+            rotation_system,
+            rotation,
+            from_piece,
+            src_reachable,
+            free_space_block,
+        );
+    }
+
+    let mut src_candidates = src_reachable.clone();
+    let mut dest_reachable = Reachable::blank();
+
+    let kicks = rotation_system.iter_kicks(from_piece, rotation).as_slice();
+    let shift = center_offset(rotation_system, from_piece.rotate(rotation))
+        - center_offset(rotation_system, from_piece);
+
+    for (index, kick) in kicks.iter().enumerate() {
+        let offset = kick.offset + shift;
+        let shift_forward = src_candidates.clone().jump_and(dest_free_space, offset);
+        dest_reachable = dest_reachable.or(&shift_forward);
+
+        let last = index == kicks.len() - 1;
+        if !last {
+            src_candidates = src_candidates.jump_rev(shift_forward, -offset);
+            if src_candidates.empty() {
+                break;
+            }
+        }
+    }
+
+    dest_reachable
+}
+
+// キックが盤面に依存するときは、1つずつテストする
+pub fn rotate_by_test_kick<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    rotation: Rotation,
+    from_piece: Piece,
+    src_reachable: &Reachable<T>,
+    free_space_block: &FreeSpace<T>,
+) -> Reachable<T> {
+    let mut dest_reachable = Reachable::blank();
+
+    for (x, &col) in src_reachable.cols.iter().enumerate() {
+        let mut col = col;
+        while col != T::ZERO {
+            let y = col.trailing_zeros();
+            col -= T::ONE << y;
+
+            let from = from_piece.with(cc(x as i32, y as i32));
+            let destination = test_kick(
+                rotation_system,
+                |location| is_free_in_block(free_space_block, location),
+                from,
+                rotation,
+            );
+            if let Some(destination) = destination {
+                let location = destination.position.to_location();
+                dest_reachable.cols[location.x as usize] |= T::ONE << location.y as u32;
+            }
+        }
+    }
+
+    dest_reachable
+}
+
+pub fn can_reach4<T: MoveBits>(reachables: &[Reachable<T>; 4], goals: &[CcPlacement]) -> bool {
+    goals.iter().any(|&goal_placement| {
+        let orientation_index = goal_placement.piece.orientation as usize;
+        let location = goal_placement.position.to_location();
+        reachables[orientation_index].is_visited(location)
+    })
+}
+
+pub fn can_reach1<T: MoveBits>(reachable: &Reachable<T>, goal: CcPlacement) -> bool {
+    let location = goal.position.to_location();
+    reachable.is_visited(location)
+}
@@ -0,0 +1,327 @@
+use std::collections::VecDeque;
+
+use fxhash::FxHashMap;
+use tinyvec::ArrayVec;
+
+use crate::boards::Board;
+use crate::coordinates::{cc, Location};
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::loaders::{
+    can_reach1, can_reach4, is_free_in_block, test_kick, FreeSpaceCache,
+};
+use crate::internal_moves::minimize::minimize;
+use crate::internal_moves::moves::{Moves1, Moves4};
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::MoveBits;
+use crate::pieces::{Orientation, Shape, ToCcPosition};
+use crate::placements::{BlPlacement, CcPlacement};
+use crate::{Rotation, RotationSystem, With};
+
+// Moving or rotating on the ground resets the lock delay, but only up to the limit.
+// When the limit is reached, the piece locks as soon as it's on the ground.
+// The count is cleared when the piece reaches a row lower than ever.
+//
+// The gravity makes the piece fall the rows when it appears and after each move or rotation.
+// 20G is `u32::MAX`, and zero means no gravity.
+//
+// Since the count depends on the path, this searches the positions one by one, not as bitboards.
+
+const ORIENTATIONS_ORDER: [Orientation; 4] = [
+    Orientation::North,
+    Orientation::East,
+    Orientation::South,
+    Orientation::West,
+];
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct State {
+    orientation_index: usize,
+    location: Location,
+    lowest_y: i32,
+}
+
+struct Searcher<'a, T: MoveBits, R: RotationSystem> {
+    rotation_system: &'a R,
+    free_spaces: &'a [FreeSpace<T>; 4],
+    free_space_block: &'a FreeSpace<T>,
+    shape: Shape,
+    allows_rotation: bool,
+    gravity: u32,
+    reset_limit: u32,
+}
+
+impl<T: MoveBits, R: RotationSystem> Searcher<'_, T, R> {
+    #[inline]
+    fn is_free(&self, orientation_index: usize, location: Location) -> bool {
+        (0..10).contains(&location.x)
+            && (0..T::BITS as i32).contains(&location.y)
+            && self.free_spaces[orientation_index].is_free_at(location)
+    }
+
+    #[inline]
+    fn is_grounded(&self, orientation_index: usize, location: Location) -> bool {
+        !self.is_free(orientation_index, Location::new(location.x, location.y - 1))
+    }
+
+    #[inline]
+    fn is_unlimited(&self) -> bool {
+        self.reset_limit == u32::MAX
+    }
+
+    #[inline]
+    fn fall(&self, orientation_index: usize, mut location: Location) -> Location {
+        let mut rows = self.gravity;
+        while 0 < rows && !self.is_grounded(orientation_index, location) {
+            location.y -= 1;
+            rows -= 1;
+        }
+        location
+    }
+
+    fn search(&self, spawn: CcPlacement) -> [Reachable<T>; 4] {
+        let mut reachables = [(); 4].map(|_| Reachable::blank());
+
+        let orientation_index = spawn.piece.orientation as usize;
+        let mut location = spawn.position.to_location();
+        if !self.is_free(orientation_index, location) {
+            return reachables;
+        }
+        location = self.fall(orientation_index, location);
+
+        let start = State {
+            orientation_index,
+            location,
+            lowest_y: if self.is_unlimited() { 0 } else { location.y },
+        };
+
+        // The minimum count of resets used for each state.
+        let mut visited = FxHashMap::<State, u32>::default();
+        let mut queue = VecDeque::<(State, u32)>::new();
+        visited.insert(start, 0);
+        queue.push_back((start, 0));
+
+        while let Some((state, count)) = queue.pop_front() {
+            if visited.get(&state).is_some_and(|&it| it < count) {
+                // Already found a better path.
+                continue;
+            }
+
+            let grounded = self.is_grounded(state.orientation_index, state.location);
+            if grounded {
+                let location = state.location;
+                reachables[state.orientation_index].cols[location.x as usize] |=
+                    T::ONE << location.y as u32;
+
+                if self.reset_limit <= count {
+                    // Locked immediately.
+                    continue;
+                }
+            }
+
+            let next_count = if grounded { count + 1 } else { count };
+            for (orientation_index, location) in self.next_locations(state, grounded) {
+                let location = self.fall(orientation_index, location);
+
+                let (lowest_y, next_count) = if self.is_unlimited() {
+                    // Without the limit, the count and the lowest row don't matter, so they are not kept.
+                    (0, 0)
+                } else if location.y < state.lowest_y {
+                    (location.y, 0)
+                } else {
+                    (state.lowest_y, next_count)
+                };
+
+                let next = State {
+                    orientation_index,
+                    location,
+                    lowest_y,
+                };
+                if visited.get(&next).is_some_and(|&it| it <= next_count) {
+                    continue;
+                }
+                visited.insert(next, next_count);
+                queue.push_back((next, next_count));
+            }
+        }
+
+        reachables
+    }
+
+    // Returns the destinations by one move or one rotation.
+    fn next_locations(&self, state: State, grounded: bool) -> ArrayVec<[(usize, Location); 5]> {
+        let mut out = ArrayVec::new();
+        let State {
+            orientation_index,
+            location,
+            ..
+        } = state;
+
+        for dx in [-1, 1] {
+            let next = Location::new(location.x + dx, location.y);
+            if self.is_free(orientation_index, next) {
+                out.push((orientation_index, next));
+            }
+        }
+
+        if !grounded {
+            out.push((orientation_index, Location::new(location.x, location.y - 1)));
+        }
+
+        if self.allows_rotation {
+            let piece = self.shape.with(ORIENTATIONS_ORDER[orientation_index]);
+            for rotation in [Rotation::Cw, Rotation::Ccw] {
+                let destination = test_kick(
+                    self.rotation_system,
+                    |location| is_free_in_block(self.free_space_block, location),
+                    piece.with(cc(location.x, location.y)),
+                    rotation,
+                );
+                if let Some(destination) = destination {
+                    out.push((
+                        destination.piece.orientation as usize,
+                        destination.position.to_location(),
+                    ));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn reachables_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> [Reachable<T>; 4] {
+    let spawn = spawn.to_cc_placement();
+    Searcher {
+        rotation_system,
+        free_spaces: cache.free_spaces(spawn.piece.shape),
+        free_space_block: &cache.free_space_block,
+        shape: spawn.piece.shape,
+        allows_rotation: true,
+        gravity,
+        reset_limit,
+    }
+    .search(spawn)
+}
+
+fn reachable_no_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: CcPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> Reachable<T> {
+    let index = spawn.piece.orientation as usize;
+    let mut free_spaces = [(); 4].map(|_| FreeSpace::new([T::ZERO; 10]));
+    free_spaces[index] = cache.free_space(spawn.piece).clone();
+
+    let mut reachables = Searcher {
+        rotation_system,
+        free_spaces: &free_spaces,
+        free_space_block: &cache.free_space_block,
+        shape: spawn.piece.shape,
+        allows_rotation: false,
+        gravity,
+        reset_limit,
+    }
+    .search(spawn);
+    std::mem::replace(&mut reachables[index], Reachable::blank())
+}
+
+pub fn moves_lock_delay_with_rotation<T: MoveBits, const MINIMIZE: bool>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> Moves4<T> {
+    let reachables = reachables_with_rotation(rotation_system, cache, spawn, gravity, reset_limit);
+
+    let reachables = if MINIMIZE {
+        minimize(reachables, spawn.piece.shape)
+    } else {
+        reachables
+    };
+
+    Moves4 {
+        spawn_piece: spawn.piece,
+        reachables,
+    }
+}
+
+pub fn moves_lock_delay_no_rotation<T: MoveBits, const MINIMIZE: bool>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> Moves1<T> {
+    let spawn = spawn.canonical_or_self().to_cc_placement();
+    let reachable = reachable_no_rotation(rotation_system, cache, spawn, gravity, reset_limit);
+
+    Moves1 {
+        spawn_piece: spawn.piece,
+        reachable,
+        minimized: MINIMIZE,
+    }
+}
+
+pub(crate) fn can_reach_lock_delay_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> bool {
+    let goals = goal
+        .piece
+        .orientations_having_same_form()
+        .iter()
+        .map(|&orientation| goal.piece.shape.with(orientation))
+        .map(|piece| piece.with(goal.position.to_cc_position(piece.to_piece_blocks())))
+        .collect::<Vec<_>>();
+
+    let cache = FreeSpaceCache::new(board);
+    let reachables = reachables_with_rotation(rotation_system, &cache, spawn, gravity, reset_limit);
+
+    can_reach4(&reachables, &goals)
+}
+
+pub(crate) fn can_reach_strictly_lock_delay_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> bool {
+    let goals = vec![goal.to_cc_placement()];
+
+    let cache = FreeSpaceCache::new(board);
+    let reachables = reachables_with_rotation(rotation_system, &cache, spawn, gravity, reset_limit);
+
+    can_reach4(&reachables, &goals)
+}
+
+pub(crate) fn can_reach_lock_delay_no_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+    gravity: u32,
+    reset_limit: u32,
+) -> bool {
+    let spawn = spawn.canonical_or_self().to_cc_placement();
+    let goal = goal.canonical_or_self().to_cc_placement();
+
+    let cache = FreeSpaceCache::new(board);
+    let reachable = reachable_no_rotation(rotation_system, &cache, spawn, gravity, reset_limit);
+
+    can_reach1(&reachable, goal)
+}
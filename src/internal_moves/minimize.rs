@@ -1,10 +1,11 @@
-use crate::internal_moves::u64::reachable::Reachable64;
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::MoveBits;
 use crate::pieces::Shape;
 use std::mem;
 
 // Extract canonical positions from the currently free positions.
 #[inline(always)]
-pub fn minimize(mut reachables: [Reachable64; 4], shape: Shape) -> [Reachable64; 4] {
+pub fn minimize<T: MoveBits>(mut reachables: [Reachable<T>; 4], shape: Shape) -> [Reachable<T>; 4] {
     for piece in shape.all_pieces_iter() {
         match piece.canonical() {
             None => continue,
@@ -14,14 +15,14 @@ pub fn minimize(mut reachables: [Reachable64; 4], shape: Shape) -> [Reachable64;
                 let offset = src_bl - dest_bl;
                 let dest_reachable = mem::replace(
                     &mut reachables[dest.orientation as usize],
-                    Reachable64::blank(),
+                    Reachable::blank(),
                 );
                 let src_reachable = mem::replace(
                     &mut reachables[piece.orientation as usize],
-                    Reachable64::blank(),
+                    Reachable::blank(),
                 );
-                reachables[dest.orientation as usize] = dest_reachable
-                    .or_shift(src_reachable, offset);
+                reachables[dest.orientation as usize] =
+                    dest_reachable.or_shift(src_reachable, offset);
             }
         }
     }
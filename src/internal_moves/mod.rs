@@ -1 +1,15 @@
-pub mod u64;
+pub use bits::*;
+
+mod bits;
+mod free;
+pub mod free_space;
+pub mod harddrop;
+pub mod lock_delay;
+pub mod loaders;
+mod minimize;
+pub mod moves;
+mod ops;
+mod reachable;
+pub mod softdrop;
+pub mod timing;
+pub mod twentyg;
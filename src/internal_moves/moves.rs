@@ -0,0 +1,48 @@
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::{ops, MoveBits};
+use crate::pieces::Piece;
+use crate::placements::PlacementSet;
+
+#[derive(Debug)]
+pub struct Moves4<T: MoveBits> {
+    pub spawn_piece: Piece,
+    pub reachables: [Reachable<T>; 4],
+}
+
+impl<T: MoveBits> Moves4<T> {
+    #[inline]
+    pub fn placement_set(&self) -> PlacementSet<T> {
+        let cols = [0, 1, 2, 3].map(|index| self.reachables[index].cols);
+        PlacementSet::new(self.spawn_piece.shape, cols)
+    }
+}
+
+#[derive(Debug)]
+pub struct Moves1<T: MoveBits> {
+    pub spawn_piece: Piece,
+    pub reachable: Reachable<T>,
+    pub minimized: bool,
+}
+
+impl<T: MoveBits> Moves1<T> {
+    #[inline]
+    pub fn placement_set(&self) -> PlacementSet<T> {
+        debug_assert!(self.spawn_piece.canonical().is_none());
+
+        let mut set = PlacementSet::blank(self.spawn_piece.shape);
+
+        if self.minimized {
+            set.cols[self.spawn_piece.orientation as usize] = self.reachable.cols;
+        } else {
+            // Since the shape looks the same in all orientations, only the center of rotation is moved.
+            let spawn_bottom_left = self.spawn_piece.to_piece_blocks().bottom_left;
+            for piece in self.spawn_piece.shape.all_pieces_iter() {
+                let offset = spawn_bottom_left - piece.to_piece_blocks().bottom_left;
+                set.cols[piece.orientation as usize] =
+                    ops::shift_by_offset(self.reachable.cols, offset);
+            }
+        }
+
+        set
+    }
+}
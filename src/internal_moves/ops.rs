@@ -1,11 +1,12 @@
 use crate::coordinates::Offset;
+use crate::internal_moves::MoveBits;
 use std::cmp::Ordering::{Equal, Greater, Less};
 
 #[inline(always)]
 #[allow(clippy::nonminimal_bool)]
-pub fn shift<const LEFT: i32, const RIGHT: i32, const DOWN: i32, const UP: i32>(
-    data: [u64; 10],
-) -> [u64; 10] {
+pub fn shift<T: MoveBits, const LEFT: i32, const RIGHT: i32, const DOWN: i32, const UP: i32>(
+    data: [T; 10],
+) -> [T; 10] {
     debug_assert!(0 <= LEFT && LEFT <= 4);
     debug_assert!(0 <= RIGHT && RIGHT <= 4);
     debug_assert!(
@@ -22,21 +23,21 @@ pub fn shift<const LEFT: i32, const RIGHT: i32, const DOWN: i32, const UP: i32>(
 
     // down or up
     let data = if 0 < DOWN {
-        data.map(|v| v >> DOWN)
+        data.map(|v| v >> DOWN as u32)
     } else if 0 < UP {
-        data.map(|v| v << UP)
+        data.map(|v| v << UP as u32)
     } else {
         data
     };
 
     // left ot right
     if 0 < LEFT {
-        let mut dest = [0u64; 10];
+        let mut dest = [T::ZERO; 10];
         let left = LEFT as usize;
         dest[..(10 - left)].copy_from_slice(&data[left..10]);
         dest
     } else if 0 < RIGHT {
-        let mut dest = [0u64; 10];
+        let mut dest = [T::ZERO; 10];
         let right = RIGHT as usize;
         dest[right..10].copy_from_slice(&data[..(10 - right)]);
         dest
@@ -47,22 +48,22 @@ pub fn shift<const LEFT: i32, const RIGHT: i32, const DOWN: i32, const UP: i32>(
 
 #[inline(always)]
 #[allow(clippy::manual_memcpy)]
-pub fn shift_by_offset(data: [u64; 10], offset: Offset) -> [u64; 10] {
+pub fn shift_by_offset<T: MoveBits>(data: [T; 10], offset: Offset) -> [T; 10] {
     if offset == Offset::new(0, 0) {
         return data;
     }
 
     // down or up
     let data = match offset.dy.cmp(&0) {
-        Less => data.map(|v| v >> -offset.dy),
-        Greater => data.map(|v| v << offset.dy),
+        Less => data.map(|v| v >> (-offset.dy) as u32),
+        Greater => data.map(|v| v << offset.dy as u32),
         Equal => data,
     };
 
     // left ot right
     match offset.dx.cmp(&0) {
         Less => {
-            let mut dest = [0u64; 10];
+            let mut dest = [T::ZERO; 10];
             let left = (-offset.dx) as usize;
             for index in 0..(10 - left) {
                 dest[index] = data[index + left];
@@ -70,7 +71,7 @@ pub fn shift_by_offset(data: [u64; 10], offset: Offset) -> [u64; 10] {
             dest
         }
         Greater => {
-            let mut dest = [0u64; 10];
+            let mut dest = [T::ZERO; 10];
             let right = offset.dx as usize;
             for index in 0..(10 - right) {
                 dest[index + right] = data[index];
@@ -83,7 +84,7 @@ pub fn shift_by_offset(data: [u64; 10], offset: Offset) -> [u64; 10] {
 
 // ボードを左右下方向にシフトしてマージ
 #[inline(always)]
-pub fn move_nr(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
+pub fn move_nr<T: MoveBits>(data: [T; 10], free_space: [T; 10]) -> [T; 10] {
     let mut data = data;
 
     {
@@ -131,7 +132,7 @@ pub fn move_nr(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
 }
 
 #[inline(always)]
-pub fn move_nl(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
+pub fn move_nl<T: MoveBits>(data: [T; 10], free_space: [T; 10]) -> [T; 10] {
     let mut data = data;
 
     {
@@ -178,8 +179,39 @@ pub fn move_nl(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
     data
 }
 
+// 接地するまで下に移動して、接地した位置だけを残す
 #[inline(always)]
-pub fn land(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
+pub fn drop_down<T: MoveBits>(data: [T; 10], free_space: [T; 10]) -> [T; 10] {
+    let mut data = data;
+    for index in 0..10 {
+        loop {
+            let d = (data[index] | (data[index] >> 1)) & free_space[index];
+            if d == data[index] {
+                break;
+            }
+            data[index] = d;
+        }
+    }
+    land(data, free_space)
+}
+
+// 左右に1つずつ移動してから接地するまで落下させてマージ
+#[inline(always)]
+pub fn move_and_drop<T: MoveBits>(data: [T; 10], free_space: [T; 10]) -> [T; 10] {
+    let mut moved = data;
+    for index in 0..10 {
+        if 0 < index {
+            moved[index] |= data[index - 1] & free_space[index];
+        }
+        if index < 9 {
+            moved[index] |= data[index + 1] & free_space[index];
+        }
+    }
+    drop_down(moved, free_space)
+}
+
+#[inline(always)]
+pub fn land<T: MoveBits>(data: [T; 10], free_space: [T; 10]) -> [T; 10] {
     [
         !(free_space[0] << 1) & data[0],
         !(free_space[1] << 1) & data[1],
@@ -195,7 +227,7 @@ pub fn land(data: [u64; 10], free_space: [u64; 10]) -> [u64; 10] {
 }
 
 #[inline(always)]
-pub fn or(left: [u64; 10], right: [u64; 10]) -> [u64; 10] {
+pub fn or<T: MoveBits>(left: [T; 10], right: [T; 10]) -> [T; 10] {
     [
         left[0] | right[0],
         left[1] | right[1],
@@ -211,7 +243,7 @@ pub fn or(left: [u64; 10], right: [u64; 10]) -> [u64; 10] {
 }
 
 #[inline(always)]
-pub fn and(left: [u64; 10], right: [u64; 10]) -> [u64; 10] {
+pub fn and<T: MoveBits>(left: [T; 10], right: [T; 10]) -> [T; 10] {
     [
         left[0] & right[0],
         left[1] & right[1],
@@ -227,7 +259,7 @@ pub fn and(left: [u64; 10], right: [u64; 10]) -> [u64; 10] {
 }
 
 #[inline(always)]
-pub fn and_not(left: [u64; 10], right: [u64; 10]) -> [u64; 10] {
+pub fn and_not<T: MoveBits>(left: [T; 10], right: [T; 10]) -> [T; 10] {
     [
         !left[0] & right[0],
         !left[1] & right[1],
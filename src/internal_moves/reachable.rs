@@ -0,0 +1,93 @@
+use crate::boards::Board;
+use crate::coordinates::{Location, Offset};
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::{ops, MoveBits};
+
+#[derive(Debug, Clone)]
+pub struct Reachable<T: MoveBits> {
+    pub(crate) cols: [T; 10],
+}
+
+impl<T: MoveBits> PartialEq for Reachable<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cols == other.cols
+    }
+}
+
+impl<T: MoveBits> Reachable<T> {
+    #[inline(always)]
+    pub fn new(cols: [T; 10]) -> Self {
+        Self { cols }
+    }
+
+    #[inline(always)]
+    pub fn blank() -> Self {
+        Self::new([T::ZERO; 10])
+    }
+
+    #[inline(always)]
+    pub fn is_visited(&self, location: Location) -> bool {
+        self.cols[location.x as usize] & (T::ONE << location.y as u32) != T::ZERO
+    }
+
+    #[inline(always)]
+    pub fn or(self, other: &Reachable<T>) -> Self {
+        Self::new(ops::or(self.cols, other.cols))
+    }
+
+    #[inline(always)]
+    pub fn empty(&self) -> bool {
+        self.cols.iter().all(|&v| v == T::ZERO)
+    }
+
+    // ボードを左右下方向にシフトしてマージ
+    #[inline(always)]
+    pub fn move_n(self, free_space: &FreeSpace<T>, left: bool) -> Self {
+        if left {
+            Self::new(ops::move_nl(self.cols, free_space.cols))
+        } else {
+            Self::new(ops::move_nr(self.cols, free_space.cols))
+        }
+    }
+
+    #[inline(always)]
+    pub fn jump_and(self, free_space: &FreeSpace<T>, offset: Offset) -> Self {
+        let shift = ops::shift_by_offset(self.cols, offset);
+        Self::new(ops::and(free_space.cols, shift))
+    }
+
+    #[inline(always)]
+    pub fn jump_rev(self, dest_jumped: Reachable<T>, offset: Offset) -> Self {
+        let shift = ops::shift_by_offset(dest_jumped.cols, offset);
+        Self::new(ops::and_not(shift, self.cols))
+    }
+
+    #[inline(always)]
+    pub fn or_shift(self, target: Reachable<T>, offset: Offset) -> Self {
+        let shift = ops::shift_by_offset(target.cols, offset);
+        Self::new(ops::or(self.cols, shift))
+    }
+
+    // 接地するまで落下
+    #[inline(always)]
+    pub fn drop_down(self, free_space: &FreeSpace<T>) -> Self {
+        Self::new(ops::drop_down(self.cols, free_space.cols))
+    }
+
+    // 左右に移動してから落下
+    #[inline(always)]
+    pub fn move_and_drop(self, free_space: &FreeSpace<T>) -> Self {
+        Self::new(ops::move_and_drop(self.cols, free_space.cols))
+    }
+
+    #[inline(always)]
+    pub fn land(self, free_space: &FreeSpace<T>) -> Self {
+        Self::new(ops::land(self.cols, free_space.cols))
+    }
+}
+
+impl<T: MoveBits> From<&Reachable<T>> for Board<T> {
+    fn from(value: &Reachable<T>) -> Self {
+        Board { cols: value.cols }
+    }
+}
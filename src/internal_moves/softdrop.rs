@@ -1,13 +1,15 @@
 use crate::array_map::zip2_map4;
 use crate::boards::Board;
-use crate::internal_moves::u64::free_space::FreeSpace64;
-use crate::internal_moves::u64::loaders::{
+use crate::internal_moves::free;
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::loaders::{
     can_reach1, can_reach4, rotate, spawn_and_harddrop_reachable, spawn_and_harddrop_reachables,
-    to_free_space, to_free_spaces,
+    to_free_space, to_free_space_block, FreeSpaceCache,
 };
-use crate::internal_moves::u64::minimize::minimize;
-use crate::internal_moves::u64::moves::{Moves1, Moves4};
-use crate::internal_moves::u64::reachable::Reachable64;
+use crate::internal_moves::minimize::minimize;
+use crate::internal_moves::moves::{Moves1, Moves4};
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::MoveBits;
 use crate::pieces::{Orientation, Piece, ToCcPosition};
 use crate::placements::{BlPlacement, CcPlacement};
 use crate::{Rotate, Rotation, RotationSystem, With};
@@ -19,12 +21,13 @@ const ORIENTATIONS_ORDER: [Orientation; 4] = [
     Orientation::West,
 ];
 
-pub(crate) fn search_with_rotation(
+pub(crate) fn search_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     spawn_piece: Piece,
-    mut reachables: [Reachable64; 4],
-    free_spaces: &[FreeSpace64; 4],
-) -> [Reachable64; 4] {
+    mut reachables: [Reachable<T>; 4],
+    free_spaces: &[FreeSpace<T>; 4],
+    free_space_block: &FreeSpace<T>,
+) -> [Reachable<T>; 4] {
     let mut needs_update: u8 = 0b1111;
 
     let mut left = [true; 4];
@@ -65,6 +68,7 @@ pub(crate) fn search_with_rotation(
                 src_piece,
                 &reachables[src_index],
                 &free_spaces[dest_index],
+                free_space_block,
             );
 
             let dest_reachable = reachables[dest_index].clone().or(&found_dest_reachable);
@@ -85,6 +89,7 @@ pub(crate) fn search_with_rotation(
                 src_piece,
                 &reachables[src_index],
                 &free_spaces[dest_index],
+                free_space_block,
             );
 
             let dest_reachable = reachables[dest_index].clone().or(&found_dest_reachable);
@@ -101,11 +106,12 @@ pub(crate) fn search_with_rotation(
     reachables
 }
 
-pub(crate) fn can_reach_with_rotation(
+pub(crate) fn can_reach_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     spawn_piece: Piece,
-    mut reachables: [Reachable64; 4],
-    free_spaces: &[FreeSpace64; 4],
+    mut reachables: [Reachable<T>; 4],
+    free_spaces: &[FreeSpace<T>; 4],
+    free_space_block: &FreeSpace<T>,
     goals: &[CcPlacement],
 ) -> bool {
     let mut needs_update: u8 = 0b1111;
@@ -148,6 +154,7 @@ pub(crate) fn can_reach_with_rotation(
                 src_piece,
                 &reachables[src_index],
                 &free_spaces[dest_index],
+                free_space_block,
             );
 
             let dest_reachable = reachables[dest_index].clone().or(&found_dest_reachable);
@@ -168,6 +175,7 @@ pub(crate) fn can_reach_with_rotation(
                 src_piece,
                 &reachables[src_index],
                 &free_spaces[dest_index],
+                free_space_block,
             );
 
             let dest_reachable = reachables[dest_index].clone().or(&found_dest_reachable);
@@ -188,10 +196,10 @@ pub(crate) fn can_reach_with_rotation(
     false
 }
 
-pub(crate) fn search_no_rotation(
-    mut reachable: Reachable64,
-    free_space: &FreeSpace64,
-) -> Reachable64 {
+pub(crate) fn search_no_rotation<T: MoveBits>(
+    mut reachable: Reachable<T>,
+    free_space: &FreeSpace<T>,
+) -> Reachable<T> {
     let mut left = false;
     loop {
         left = !left;
@@ -208,9 +216,9 @@ pub(crate) fn search_no_rotation(
     reachable
 }
 
-pub(crate) fn can_reach_no_rotation(
-    mut reachable: Reachable64,
-    free_space: &FreeSpace64,
+pub(crate) fn can_reach_no_rotation<T: MoveBits>(
+    mut reachable: Reachable<T>,
+    free_space: &FreeSpace<T>,
     goal: CcPlacement,
 ) -> bool {
     let mut left = false;
@@ -234,20 +242,28 @@ pub(crate) fn can_reach_no_rotation(
     false
 }
 
-pub fn moves_softdrop_with_rotation<const MINIMIZE: bool>(
+pub fn moves_softdrop_with_rotation<T: MoveBits, const MINIMIZE: bool>(
     rotation_system: &impl RotationSystem,
-    board: &Board<u64>,
+    cache: &FreeSpaceCache<T>,
     spawn: BlPlacement,
-) -> Moves4 {
+) -> Moves4<T> {
     let spawn = spawn.to_cc_placement();
-    let free_spaces = to_free_spaces(board, spawn.piece.shape);
-    let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, &free_spaces);
-    let reachables = search_with_rotation(rotation_system, spawn.piece, reachables, &free_spaces);
+    let free_spaces = cache.free_spaces(spawn.piece.shape);
+    let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, free_spaces);
+    let reachables = search_with_rotation(
+        rotation_system,
+        spawn.piece,
+        reachables,
+        free_spaces,
+        &cache.free_space_block,
+    );
 
     // landed
-    let reachables = zip2_map4(reachables, free_spaces, |reachable, free_space| {
-        reachable.land(&free_space)
-    });
+    let reachables = zip2_map4(
+        reachables,
+        free_spaces.each_ref(),
+        |reachable, free_space| reachable.land(free_space),
+    );
 
     let reachables = if MINIMIZE {
         minimize(reachables, spawn.piece.shape)
@@ -261,16 +277,16 @@ pub fn moves_softdrop_with_rotation<const MINIMIZE: bool>(
     }
 }
 
-pub fn moves_softdrop_no_rotation<const MINIMIZE: bool>(
-    board: &Board<u64>,
+pub fn moves_softdrop_no_rotation<T: MoveBits, const MINIMIZE: bool>(
+    cache: &FreeSpaceCache<T>,
     spawn: BlPlacement,
-) -> Moves1 {
+) -> Moves1<T> {
     let spawn = spawn.canonical_or_self().to_cc_placement();
-    let free_space = to_free_space(board, spawn.piece);
-    let reachable = spawn_and_harddrop_reachable(spawn, &free_space);
+    let free_space = cache.free_space(spawn.piece);
+    let reachable = spawn_and_harddrop_reachable(spawn, free_space);
 
-    let reachable = search_no_rotation(reachable, &free_space);
-    let reachable = reachable.land(&free_space);
+    let reachable = search_no_rotation(reachable, free_space);
+    let reachable = reachable.land(free_space);
 
     Moves1 {
         spawn_piece: spawn.piece,
@@ -279,10 +295,10 @@ pub fn moves_softdrop_no_rotation<const MINIMIZE: bool>(
     }
 }
 
-pub(crate) fn can_reach_softdrop_with_rotation(
+pub(crate) fn can_reach_softdrop_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.to_cc_placement();
@@ -294,7 +310,8 @@ pub(crate) fn can_reach_softdrop_with_rotation(
         .map(|piece| piece.with(goal.position.to_cc_position(piece.to_piece_blocks())))
         .collect::<Vec<_>>();
 
-    let free_spaces = to_free_spaces(board, spawn.piece.shape);
+    let free_space_block = to_free_space_block(board);
+    let free_spaces = free::to_free_spaces(free_space_block.clone(), spawn.piece.shape);
     let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, &free_spaces);
 
     if can_reach4(&reachables, &goals) {
@@ -306,20 +323,22 @@ pub(crate) fn can_reach_softdrop_with_rotation(
         spawn.piece,
         reachables,
         &free_spaces,
+        &free_space_block,
         &goals,
     )
 }
 
-pub(crate) fn can_reach_strictly_softdrop_with_rotation(
+pub(crate) fn can_reach_strictly_softdrop_with_rotation<T: MoveBits>(
     rotation_system: &impl RotationSystem,
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.to_cc_placement();
     let goals = vec![goal.to_cc_placement()];
 
-    let free_spaces = to_free_spaces(board, spawn.piece.shape);
+    let free_space_block = to_free_space_block(board);
+    let free_spaces = free::to_free_spaces(free_space_block.clone(), spawn.piece.shape);
     let reachables = spawn_and_harddrop_reachables(rotation_system, spawn, &free_spaces);
 
     if can_reach4(&reachables, &goals) {
@@ -331,17 +350,18 @@ pub(crate) fn can_reach_strictly_softdrop_with_rotation(
         spawn.piece,
         reachables,
         &free_spaces,
+        &free_space_block,
         &goals,
     )
 }
 
-pub(crate) fn can_reach_softdrop_no_rotation(
+pub(crate) fn can_reach_softdrop_no_rotation<T: MoveBits>(
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     let spawn = spawn.canonical_or_self().to_cc_placement();
-    let goal = goal.to_cc_placement();
+    let goal = goal.canonical_or_self().to_cc_placement();
 
     let free_space = to_free_space(board, spawn.piece);
     let reachable = spawn_and_harddrop_reachable(spawn, &free_space);
@@ -353,9 +373,9 @@ pub(crate) fn can_reach_softdrop_no_rotation(
     can_reach_no_rotation(reachable, &free_space, goal)
 }
 
-pub(crate) fn can_reach_strictly_softdrop_no_rotation(
+pub(crate) fn can_reach_strictly_softdrop_no_rotation<T: MoveBits>(
     goal: BlPlacement,
-    board: &Board<u64>,
+    board: &Board<T>,
     spawn: BlPlacement,
 ) -> bool {
     can_reach_softdrop_no_rotation(goal, board, spawn)
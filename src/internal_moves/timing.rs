@@ -0,0 +1,271 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use fxhash::FxHashMap;
+use tinyvec::ArrayVec;
+
+use crate::coordinates::{cc, Location};
+use crate::internal_moves::free;
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::loaders::{is_free_in_block, test_kick};
+use crate::internal_moves::MoveBits;
+use crate::pieces::{Orientation, Shape};
+use crate::placements::CcPlacement;
+use crate::{AllowMove, Handling, Input, Rotation, RotationSystem, With};
+
+// Searches the fastest inputs by Dijkstra's algorithm over the positions of the piece.
+// Each input is an edge weighted by the frames it takes, so it searches the positions one by one like the lock delay.
+
+const ORIENTATIONS_ORDER: [Orientation; 4] = [
+    Orientation::North,
+    Orientation::East,
+    Orientation::South,
+    Orientation::West,
+];
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+struct State {
+    orientation_index: usize,
+    location: Location,
+}
+
+struct Searcher<'a, T: MoveBits, R: RotationSystem> {
+    rotation_system: &'a R,
+    free_spaces: &'a [FreeSpace<T>; 4],
+    free_space_block: &'a FreeSpace<T>,
+    shape: Shape,
+    allow_move: AllowMove,
+    handling: &'a Handling,
+}
+
+impl<T: MoveBits, R: RotationSystem> Searcher<'_, T, R> {
+    #[inline]
+    fn is_free(&self, state: State) -> bool {
+        let location = state.location;
+        (0..10).contains(&location.x)
+            && (0..T::BITS as i32).contains(&location.y)
+            && self.free_spaces[state.orientation_index].is_free_at(location)
+    }
+
+    #[inline]
+    fn is_grounded(&self, state: State) -> bool {
+        !self.is_free(self.shift(state, 0, -1))
+    }
+
+    #[inline]
+    fn shift(&self, state: State, dx: i32, dy: i32) -> State {
+        State {
+            orientation_index: state.orientation_index,
+            location: Location::new(state.location.x + dx, state.location.y + dy),
+        }
+    }
+
+    #[inline]
+    fn drop_down(&self, mut state: State) -> State {
+        while !self.is_grounded(state) {
+            state.location.y -= 1;
+        }
+        state
+    }
+
+    // Applies the gravity of 20G after each input.
+    #[inline]
+    fn settle(&self, state: State) -> State {
+        if self.allow_move == AllowMove::TwentyG {
+            self.drop_down(state)
+        } else {
+            state
+        }
+    }
+
+    // Returns the destinations by one input and the frames it takes.
+    // At most 9 cells to each side, 2 rotations and a soft drop.
+    fn next_states(&self, state: State) -> ArrayVec<[(State, Input, u32); 21]> {
+        let handling = self.handling;
+        let mut out = ArrayVec::new();
+
+        for dx in [-1, 1] {
+            let (tap, das): (Input, fn(u32) -> Input) = if dx < 0 {
+                (Input::Left, Input::DasLeft)
+            } else {
+                (Input::Right, Input::DasRight)
+            };
+            let mut current = state;
+            let mut cells = 0;
+            loop {
+                let next = self.shift(current, dx, 0);
+                if !self.is_free(next) {
+                    break;
+                }
+                current = self.settle(next);
+                cells += 1;
+
+                if cells == 1 {
+                    out.push((current, tap, 1));
+                } else if 0 < handling.arr {
+                    let frames = 1 + handling.das + handling.arr * (cells - 2);
+                    out.push((current, das(cells), frames));
+                }
+            }
+
+            // The auto-repeat without delay moves to the wall at once.
+            if handling.arr == 0 && 2 <= cells {
+                out.push((current, das(cells), 1 + handling.das));
+            }
+        }
+
+        let piece = self.shape.with(ORIENTATIONS_ORDER[state.orientation_index]);
+        for (rotation, input) in [(Rotation::Cw, Input::Cw), (Rotation::Ccw, Input::Ccw)] {
+            let destination = test_kick(
+                self.rotation_system,
+                |location| is_free_in_block(self.free_space_block, location),
+                piece.with(cc(state.location.x, state.location.y)),
+                rotation,
+            );
+            if let Some(destination) = destination {
+                let next = State {
+                    orientation_index: destination.piece.orientation as usize,
+                    location: destination.position.to_location(),
+                };
+                out.push((self.settle(next), input, 1));
+            }
+        }
+
+        if self.allow_move == AllowMove::Softdrop && !self.is_grounded(state) {
+            match handling.soft_drop_frames_per_row() {
+                Some(frames) => out.push((self.shift(state, 0, -1), Input::SoftDrop(1), frames)),
+                None => {
+                    let ground = self.drop_down(state);
+                    let rows = (state.location.y - ground.location.y) as u32;
+                    out.push((ground, Input::SoftDrop(rows), 1));
+                }
+            }
+        }
+
+        out
+    }
+
+    // Returns the locked states by one input and the frames it takes.
+    fn lock_states(&self, state: State) -> ArrayVec<[(State, Input, u32); 2]> {
+        let mut out = ArrayVec::new();
+        if self.handling.harddrop {
+            out.push((self.drop_down(state), Input::HardDrop, 1));
+        }
+        if self.is_grounded(state) {
+            out.push((state, Input::Lock, self.handling.lock_delay));
+        }
+        out
+    }
+
+    fn search(
+        &self,
+        seeds: &[(CcPlacement, Option<Input>)],
+    ) -> Vec<(CcPlacement, u32, Vec<Input>)> {
+        let mut frames = FxHashMap::<State, u32>::default();
+        let mut parents = FxHashMap::<State, (Option<State>, Input)>::default();
+        let mut locked = FxHashMap::<State, (u32, State, Input)>::default();
+        let mut heap = BinaryHeap::<Reverse<(u32, State)>>::new();
+
+        for &(seed, input) in seeds {
+            let state = State {
+                orientation_index: seed.piece.orientation as usize,
+                location: seed.position.to_location(),
+            };
+            if !self.is_free(state) {
+                continue;
+            }
+            let state = self.settle(state);
+            if frames.contains_key(&state) {
+                continue;
+            }
+            frames.insert(state, 0);
+            if let Some(input) = input {
+                parents.insert(state, (None, input));
+            }
+            heap.push(Reverse((0, state)));
+        }
+
+        while let Some(Reverse((current_frames, state))) = heap.pop() {
+            if frames.get(&state).is_some_and(|&it| it < current_frames) {
+                // Already found a faster path.
+                continue;
+            }
+
+            for (next, input, cost) in self.lock_states(state) {
+                let next_frames = current_frames + cost;
+                if locked.get(&next).is_some_and(|&(it, ..)| it <= next_frames) {
+                    continue;
+                }
+                locked.insert(next, (next_frames, state, input));
+            }
+
+            for (next, input, cost) in self.next_states(state) {
+                let next_frames = current_frames + cost;
+                if frames.get(&next).is_some_and(|&it| it <= next_frames) {
+                    continue;
+                }
+                frames.insert(next, next_frames);
+                parents.insert(next, (Some(state), input));
+                heap.push(Reverse((next_frames, next)));
+            }
+        }
+
+        locked
+            .into_iter()
+            .map(|(state, (frames, from, input))| {
+                let placement = self
+                    .shape
+                    .with(ORIENTATIONS_ORDER[state.orientation_index])
+                    .with(cc(state.location.x, state.location.y));
+                (placement, frames, trace(&parents, from, input))
+            })
+            .collect()
+    }
+}
+
+// Returns the inputs from the seed, merging the consecutive soft drops.
+fn trace(
+    parents: &FxHashMap<State, (Option<State>, Input)>,
+    mut state: State,
+    last: Input,
+) -> Vec<Input> {
+    let mut inputs = vec![last];
+    while let Some(&(parent, input)) = parents.get(&state) {
+        inputs.push(input);
+        match parent {
+            Some(parent) => state = parent,
+            None => break,
+        }
+    }
+    inputs.reverse();
+
+    let mut merged = Vec::<Input>::with_capacity(inputs.len());
+    for input in inputs {
+        match (merged.last_mut(), input) {
+            (Some(Input::SoftDrop(rows)), Input::SoftDrop(more)) => *rows += more,
+            _ => merged.push(input),
+        }
+    }
+    merged
+}
+
+// Returns the locked placements with the fastest frames and inputs. The seeds are the placements at the spawn.
+pub fn fastest_inputs<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    free_space_block: &FreeSpace<T>,
+    shape: Shape,
+    seeds: &[(CcPlacement, Option<Input>)],
+    allow_move: AllowMove,
+    handling: &Handling,
+) -> Vec<(CcPlacement, u32, Vec<Input>)> {
+    let free_spaces = free::to_free_spaces(free_space_block.clone(), shape);
+    Searcher {
+        rotation_system,
+        free_spaces: &free_spaces,
+        free_space_block,
+        shape,
+        allow_move,
+        handling,
+    }
+    .search(seeds)
+}
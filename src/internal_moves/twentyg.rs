@@ -0,0 +1,226 @@
+use crate::boards::Board;
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::loaders::{
+    can_reach1, can_reach4, rotate, to_free_space, FreeSpaceCache,
+};
+use crate::internal_moves::minimize::minimize;
+use crate::internal_moves::moves::{Moves1, Moves4};
+use crate::internal_moves::reachable::Reachable;
+use crate::internal_moves::MoveBits;
+use crate::pieces::{Orientation, Piece, ToCcPosition};
+use crate::placements::{BlPlacement, CcPlacement};
+use crate::{Rotate, Rotation, RotationSystem, With};
+
+// With 20G, the piece drops to the ground immediately after spawning, moving and rotating.
+// So the reachable positions are always on the ground.
+
+const ORIENTATIONS_ORDER: [Orientation; 4] = [
+    Orientation::North,
+    Orientation::East,
+    Orientation::South,
+    Orientation::West,
+];
+
+#[inline(always)]
+fn spawn_and_drop_reachable<T: MoveBits>(
+    spawn: CcPlacement,
+    free_space: &FreeSpace<T>,
+) -> Reachable<T> {
+    let location = spawn.position.to_location();
+    let mut reachable = Reachable::blank();
+    reachable.cols[location.x as usize] = T::ONE << location.y as u32;
+    reachable.drop_down(free_space)
+}
+
+#[inline(always)]
+fn spawn_and_drop_reachables<T: MoveBits>(
+    spawn: CcPlacement,
+    free_spaces: &[FreeSpace<T>; 4],
+) -> [Reachable<T>; 4] {
+    let index = spawn.piece.orientation as usize;
+    let mut reachables = [(); 4].map(|_| Reachable::blank());
+    reachables[index] = spawn_and_drop_reachable(spawn, &free_spaces[index]);
+    reachables
+}
+
+fn search_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    spawn_piece: Piece,
+    mut reachables: [Reachable<T>; 4],
+    free_spaces: &[FreeSpace<T>; 4],
+    free_space_block: &FreeSpace<T>,
+) -> [Reachable<T>; 4] {
+    let mut needs_update: u8 = 1 << spawn_piece.orientation as usize;
+
+    let mut current_index: usize = spawn_piece.orientation as usize;
+    while needs_update != 0 {
+        // if the current index is not updated, skip it.
+        if needs_update & (1 << current_index) == 0 {
+            current_index = (current_index + 1) % ORIENTATIONS_ORDER.len();
+            continue;
+        }
+        needs_update -= 1 << current_index;
+
+        // initialize
+        let src_piece = Piece::new(spawn_piece.shape, ORIENTATIONS_ORDER[current_index]);
+        let src_index = current_index;
+
+        // move
+        reachables[src_index] =
+            search_no_rotation(reachables[src_index].clone(), &free_spaces[src_index]);
+
+        // rotate
+        for rotation in [Rotation::Cw, Rotation::Ccw] {
+            let dest_index = src_piece.rotate(rotation).orientation as usize;
+
+            let found_dest_reachable = rotate(
+                rotation_system,
+                rotation,
+                src_piece,
+                &reachables[src_index],
+                &free_spaces[dest_index],
+                free_space_block,
+            )
+            .drop_down(&free_spaces[dest_index]);
+
+            let dest_reachable = reachables[dest_index].clone().or(&found_dest_reachable);
+
+            if reachables[dest_index] != dest_reachable {
+                reachables[dest_index] = dest_reachable;
+                needs_update |= 1 << dest_index;
+            }
+        }
+
+        current_index = (current_index + 1) % ORIENTATIONS_ORDER.len();
+    }
+
+    reachables
+}
+
+fn search_no_rotation<T: MoveBits>(
+    mut reachable: Reachable<T>,
+    free_space: &FreeSpace<T>,
+) -> Reachable<T> {
+    if reachable.empty() {
+        return reachable;
+    }
+
+    loop {
+        let new_reachable = reachable.clone().move_and_drop(free_space);
+        if reachable == new_reachable {
+            break;
+        }
+        reachable = new_reachable;
+    }
+
+    reachable
+}
+
+fn reachables_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+) -> [Reachable<T>; 4] {
+    let spawn = spawn.to_cc_placement();
+    let free_spaces = cache.free_spaces(spawn.piece.shape);
+    let reachables = spawn_and_drop_reachables(spawn, free_spaces);
+    search_with_rotation(
+        rotation_system,
+        spawn.piece,
+        reachables,
+        free_spaces,
+        &cache.free_space_block,
+    )
+}
+
+pub fn moves_twentyg_with_rotation<T: MoveBits, const MINIMIZE: bool>(
+    rotation_system: &impl RotationSystem,
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+) -> Moves4<T> {
+    let reachables = reachables_with_rotation(rotation_system, cache, spawn);
+
+    let reachables = if MINIMIZE {
+        minimize(reachables, spawn.piece.shape)
+    } else {
+        reachables
+    };
+
+    Moves4 {
+        spawn_piece: spawn.piece,
+        reachables,
+    }
+}
+
+pub fn moves_twentyg_no_rotation<T: MoveBits, const MINIMIZE: bool>(
+    cache: &FreeSpaceCache<T>,
+    spawn: BlPlacement,
+) -> Moves1<T> {
+    let spawn = spawn.canonical_or_self().to_cc_placement();
+    let free_space = cache.free_space(spawn.piece);
+    let reachable = spawn_and_drop_reachable(spawn, free_space);
+    let reachable = search_no_rotation(reachable, free_space);
+
+    Moves1 {
+        spawn_piece: spawn.piece,
+        reachable,
+        minimized: MINIMIZE,
+    }
+}
+
+pub(crate) fn can_reach_twentyg_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+) -> bool {
+    let goals = goal
+        .piece
+        .orientations_having_same_form()
+        .iter()
+        .map(|&orientation| goal.piece.shape.with(orientation))
+        .map(|piece| piece.with(goal.position.to_cc_position(piece.to_piece_blocks())))
+        .collect::<Vec<_>>();
+
+    let cache = FreeSpaceCache::new(board);
+    let reachables = reachables_with_rotation(rotation_system, &cache, spawn);
+
+    can_reach4(&reachables, &goals)
+}
+
+pub(crate) fn can_reach_strictly_twentyg_with_rotation<T: MoveBits>(
+    rotation_system: &impl RotationSystem,
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+) -> bool {
+    let goals = vec![goal.to_cc_placement()];
+
+    let cache = FreeSpaceCache::new(board);
+    let reachables = reachables_with_rotation(rotation_system, &cache, spawn);
+
+    can_reach4(&reachables, &goals)
+}
+
+pub(crate) fn can_reach_twentyg_no_rotation<T: MoveBits>(
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+) -> bool {
+    let spawn = spawn.canonical_or_self().to_cc_placement();
+    let goal = goal.canonical_or_self().to_cc_placement();
+
+    let free_space = to_free_space(board, spawn.piece);
+    let reachable = spawn_and_drop_reachable(spawn, &free_space);
+    let reachable = search_no_rotation(reachable, &free_space);
+
+    can_reach1(&reachable, goal)
+}
+
+pub(crate) fn can_reach_strictly_twentyg_no_rotation<T: MoveBits>(
+    goal: BlPlacement,
+    board: &Board<T>,
+    spawn: BlPlacement,
+) -> bool {
+    can_reach_twentyg_no_rotation(goal, board, spawn)
+}
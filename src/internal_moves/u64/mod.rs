@@ -1,9 +0,0 @@
-mod free;
-mod free_space;
-pub mod harddrop;
-mod loaders;
-pub mod moves;
-mod opu64;
-mod reachable;
-pub mod softdrop;
-mod minimize;
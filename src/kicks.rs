@@ -2,8 +2,8 @@ use std::fmt;
 use std::slice::Iter;
 
 use crate::boards::BoardOp;
-use crate::coordinates::Offset;
-use crate::pieces::{Piece, Shape};
+use crate::coordinates::{Location, Offset};
+use crate::pieces::{Piece, PieceBlocks, PieceBlocksFactory, PieceBlocksTable, Shape};
 use crate::placements::CcPlacement;
 use crate::{Rotate, Rotation};
 
@@ -43,22 +43,43 @@ pub trait RotationSystem {
     /// For example, in SRS, Shape::O does not move when rotated, so it's false.
     fn is_moving_in_rotation(&self, shape: Shape) -> bool;
 
-    /// Test the kick of the piece as it rotates on the board.
+    /// Returns the blocks of the piece relative to the center of rotation.
+    /// The centers of placements (`CcPlacement`) passed to and returned from this rotation system are based on them.
+    ///
+    /// The forms of the blocks must be the same as `PieceBlocksFactory`, and only the positions relative to the center can differ.
+    /// Move generation converts the kicks, so `BlPlacement` and `TrPlacement` are the same regardless of the blocks.
+    fn piece_blocks(&self, piece: Piece) -> &PieceBlocks {
+        PieceBlocksFactory.get(piece)
+    }
+
+    /// Returns true if the kicks depend only on the piece and the rotation.
+    /// In that case, `test_kick()` is the same as testing `iter_kicks()` in order, and move generation tests the kicks of many placements at once.
+    /// If false, move generation calls `test_kick_with()` for each placement.
+    fn has_static_kicks(&self) -> bool {
+        true
+    }
+
+    /// Test the kick of the piece as it rotates.
+    /// `is_free` returns true if the location is inside the board and has no block.
     ///
     /// Returns the final kick and placement if the test passes.
     /// Returns None if the rotation is not possible.
-    fn test_kick(
+    ///
+    /// Rotation systems whose kicks depend on the surrounding blocks override it and return false from `has_static_kicks()`.
+    fn test_kick_with(
         &self,
-        board: &impl BoardOp,
-        placement: impl Into<CcPlacement>,
+        is_free: impl Fn(Location) -> bool,
+        placement: CcPlacement,
         rotation: Rotation,
     ) -> Option<TestKickResult> {
-        let from: CcPlacement = placement.into();
-        let to = from.rotate(rotation);
+        let to = placement.rotate(rotation);
 
-        for (test_index, kick) in self.iter_kicks(from.piece, rotation).enumerate() {
+        for (test_index, kick) in self.iter_kicks(placement.piece, rotation).enumerate() {
             let destination = to + kick.offset;
-            if destination.is_in_free_space(board) {
+            let locations = self
+                .piece_blocks(destination.piece)
+                .to_locations(destination.position);
+            if locations.into_iter().all(&is_free) {
                 return Some(TestKickResult {
                     test_index,
                     kick: *kick,
@@ -69,4 +90,28 @@ pub trait RotationSystem {
 
         None
     }
+
+    /// Test the kick of the piece as it rotates on the board.
+    ///
+    /// Returns the final kick and placement if the test passes.
+    /// Returns None if the rotation is not possible.
+    fn test_kick(
+        &self,
+        board: &impl BoardOp,
+        placement: impl Into<CcPlacement>,
+        rotation: Rotation,
+    ) -> Option<TestKickResult> {
+        self.test_kick_with(
+            |location| board.test_access(location) && board.is_free_at(location),
+            placement.into(),
+            rotation,
+        )
+    }
+}
+
+impl<T: RotationSystem> PieceBlocksTable for T {
+    #[inline]
+    fn get(&self, piece: Piece) -> &PieceBlocks {
+        self.piece_blocks(piece)
+    }
 }
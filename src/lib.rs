@@ -1,30 +1,43 @@
+pub use cancellation::*;
 pub use enums::*;
+pub use game_rules::*;
 pub use kicks::*;
 pub use moves::*;
 pub use order::*;
+pub use perft::*;
 pub use rotation::*;
+pub use spins::*;
+pub use timing::*;
 pub use traits::*;
 
 #[doc(hidden)]
 pub mod prelude {
-    pub use crate::{boards::*, coordinates::*, pieces::*, placements::*, srs::SrsKickTable};
-    pub use crate::{enums::*, kicks::*, moves::*, order::*, rotation::*, traits::*};
+    pub use crate::{ars::ArsKickTable, nrs::NrsKickTable, srs::SrsKickTable};
+    pub use crate::{boards::*, coordinates::*, pieces::*, placements::*};
+    pub use crate::{cancellation::*, enums::*, game_rules::*, kicks::*, moves::*, order::*, perft::*, rotation::*, spins::*, timing::*, traits::*};
 }
 
 // Exposed modules
 pub mod boards;
 pub mod coordinates;
 pub mod macros;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod pieces;
 pub mod placements;
 
 // Internals
+mod cancellation;
 mod enums;
+mod game_rules;
 mod internal_macros;
 mod internal_moves;
 mod kicks;
 mod moves;
 mod order;
+mod perft;
 mod rotation;
+mod spins;
+mod timing;
 mod traits;
 mod array_map;
@@ -1,19 +1,57 @@
-use crate::boards::Board64;
+use thiserror::Error;
+use tinyvec::ArrayVec;
+
+use crate::ars::ArsKickTable;
+use crate::boards::{Board, BoardOp};
 use crate::internal_macros::enum_display;
-use crate::internal_moves::u64::{harddrop, softdrop};
-use crate::placements::BlPlacement;
+use crate::internal_moves::free_space::FreeSpace;
+use crate::internal_moves::loaders::FreeSpaceCache;
+use crate::internal_moves::{harddrop, loaders, lock_delay, softdrop, twentyg};
+use crate::nrs::NrsKickTable;
+use crate::pieces::Shape;
+use crate::placements::{BlPlacement, PlacementSet};
 use crate::srs::SrsKickTable;
-use crate::RotationSystem;
+use crate::{Rotation, RotationSystem};
+
+pub use crate::internal_moves::MoveBits;
 
 /// A collection of piece drop types.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub enum AllowMove {
+    /// The piece can move freely before it locks, as with slow gravity.
     #[default]
     Softdrop,
+    /// The piece can move and rotate only at the spawn height, and then drops.
     Harddrop,
+    /// The piece drops to the ground immediately (20G).
+    /// It can move and rotate only on the ground, so it cannot climb over walls higher than the ground it's on.
+    TwentyG,
+}
+
+enum_display! { AllowMove, has Softdrop,Harddrop,TwentyG }
+
+/// A collection of how the piece appears at the spawn.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum SpawnPolicy {
+    /// The piece appears only at the spawn placement.
+    #[default]
+    Normal,
+    /// The piece can also appear rotated by holding the rotation input before it spawns (IRS).
+    /// The rotated spawn is tested with the kicks of the rotation system, and the piece can appear even if the spawn placement is blocked.
+    /// The initial hold (IHS) doesn't change the placements of the piece, so choose the spawn shape with the order of the pieces instead.
+    InitialRotation,
 }
 
-enum_display! { AllowMove, has Softdrop,Harddrop }
+enum_display! { SpawnPolicy, has Normal,InitialRotation }
+
+/// A collection of errors that occur during move generation.
+#[derive(Error, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum MoveError {
+    #[error("The spawn is outside the board or overlaps blocks: {0}")]
+    InvalidSpawn(BlPlacement),
+    #[error("The shapes of the goal and the spawn are mismatched: goal={goal}, spawn={spawn}")]
+    ShapeMismatch { goal: Shape, spawn: Shape },
+}
 
 /// Rules to be applied during move generation.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -23,6 +61,13 @@ where
 {
     pub rotation_system: &'a T,
     pub allow_move: AllowMove,
+    /// The maximum number of moves and rotations on the ground that reset the lock delay.
+    /// None means the piece never locks until it's dropped. See `with_move_reset_limit()`.
+    pub move_reset_limit: Option<u32>,
+    /// The rows the piece falls by the gravity when it appears and after each move or rotation.
+    /// None means no gravity. See `with_gravity()`.
+    pub gravity: Option<u32>,
+    pub spawn_policy: SpawnPolicy,
 }
 
 impl Default for MoveRules<'_, SrsKickTable> {
@@ -38,10 +83,29 @@ impl MoveRules<'_, SrsKickTable> {
         Self {
             rotation_system: &SrsKickTable,
             allow_move,
+            move_reset_limit: None,
+            gravity: None,
+            spawn_policy: SpawnPolicy::Normal,
         }
     }
 }
 
+impl MoveRules<'_, ArsKickTable> {
+    /// The rules with ARS as in TGM2. Use `MoveRules::new(&ArsKickTable::TGM3, allow_move)` for the floor kicks.
+    #[inline]
+    pub fn ars(allow_move: AllowMove) -> Self {
+        Self::new(&ArsKickTable::TGM2, allow_move)
+    }
+}
+
+impl MoveRules<'_, NrsKickTable> {
+    /// The rules with NRS as in NES. See also `GameRules::nes()`.
+    #[inline]
+    pub fn nrs(allow_move: AllowMove) -> Self {
+        Self::new(&NrsKickTable, allow_move)
+    }
+}
+
 impl<'a, T> MoveRules<'a, T>
 where
     T: RotationSystem,
@@ -51,88 +115,461 @@ where
         Self {
             rotation_system,
             allow_move,
+            move_reset_limit: None,
+            gravity: None,
+            spawn_policy: SpawnPolicy::Normal,
+        }
+    }
+
+    /// Returns the rules that limit the moves and rotations on the ground, like the lock delay in the guideline (the limit is 15).
+    ///
+    /// Each move or rotation starting on the ground uses one reset, and the piece locks as soon as it's on the ground after the limit is reached.
+    /// The count is cleared when the piece reaches a row lower than ever.
+    /// It applies to `AllowMove::Softdrop` and `AllowMove::TwentyG`. The search is slower than the one without the limit.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// // With 20G, the piece slides on the ground from the spawn column.
+    /// let board = Board64::blank();
+    /// let spawn = piece!(ON).with(bl(4, 20));
+    /// let goal = piece!(ON).with(bl(0, 0));
+    ///
+    /// let rules = MoveRules::srs(AllowMove::TwentyG);
+    /// assert!(rules.can_reach(goal, board, spawn));
+    /// assert!(rules.with_move_reset_limit(4).can_reach(goal, board, spawn));
+    /// assert!(!rules.with_move_reset_limit(3).can_reach(goal, board, spawn));
+    /// ```
+    #[inline]
+    pub fn with_move_reset_limit(self, limit: u32) -> Self {
+        Self {
+            move_reset_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Returns the rules with the gravity slower than 20G: the piece falls the rows when it appears and after each move or rotation.
+    /// The piece can slide on the ground, and it can still fall faster by the soft drop.
+    ///
+    /// It applies to `AllowMove::Softdrop`. Use `AllowMove::TwentyG` for 20G. It can be combined with `with_move_reset_limit()`.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// // The piece needs to cross the wall before it falls below the top of the wall.
+    /// let board = Board64::from_str(&"......#...\n".repeat(19)).unwrap();
+    /// let spawn = piece!(ON).with(bl(4, 20));
+    /// let goal = piece!(ON).with(bl(8, 0));
+    ///
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// assert!(rules.can_reach(goal, board, spawn));
+    /// assert!(rules.with_gravity(1).can_reach(goal, board, spawn));
+    /// assert!(!rules.with_gravity(2).can_reach(goal, board, spawn));
+    /// ```
+    #[inline]
+    pub fn with_gravity(self, rows: u32) -> Self {
+        Self {
+            gravity: Some(rows),
+            ..self
+        }
+    }
+
+    /// Returns the rules with the spawn policy.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// // The spawn is blocked, but the rotated piece can appear.
+    /// let mut board = Board64::blank();
+    /// board.set_at(Location::new(3, 20));
+    /// let spawn = piece!(IN).with(bl(3, 20));
+    ///
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// assert!(rules.try_generate_minimized_moves(board, spawn).is_err());
+    ///
+    /// let rules = rules.with_spawn_policy(SpawnPolicy::InitialRotation);
+    /// assert_eq!(rules.generate_minimized_moves(board, spawn).len(), 22);
+    /// ```
+    #[inline]
+    pub fn with_spawn_policy(self, spawn_policy: SpawnPolicy) -> Self {
+        Self {
+            spawn_policy,
+            ..self
+        }
+    }
+
+    /// Returns the placements where the piece can appear according to the spawn policy.
+    /// It's empty if the piece cannot appear.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop).with_spawn_policy(SpawnPolicy::InitialRotation);
+    /// let spawns = rules.spawns(Board64::blank(), piece!(TN).with(bl(3, 20)));
+    /// assert_eq!(
+    ///     spawns.as_slice(),
+    ///     &[piece!(TN).with(bl(3, 20)), piece!(TE).with(bl(4, 19)), piece!(TW).with(bl(3, 19))],
+    /// );
+    /// ```
+    #[inline]
+    pub fn spawns<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> ArrayVec<[BlPlacement; 3]> {
+        self.spawns_in(&loaders::to_free_space_block(&board), spawn)
+    }
+
+    #[inline]
+    fn spawns_in<B: MoveBits>(
+        &self,
+        free_space_block: &FreeSpace<B>,
+        spawn: BlPlacement,
+    ) -> ArrayVec<[BlPlacement; 3]> {
+        let mut spawns = ArrayVec::new();
+        if loaders::is_in_free_space_block(free_space_block, spawn) {
+            spawns.push(spawn);
+        }
+
+        if self.spawn_policy == SpawnPolicy::Normal
+            || !self
+                .rotation_system
+                .is_moving_in_rotation(spawn.piece.shape)
+        {
+            return spawns;
+        }
+
+        let from = spawn.to_cc_placement();
+        for rotation in [Rotation::Cw, Rotation::Ccw] {
+            let destination = loaders::test_kick(
+                self.rotation_system,
+                |location| loaders::is_free_in_block(free_space_block, location),
+                from,
+                rotation,
+            );
+            if let Some(destination) = destination {
+                spawns.push(destination.to_bl_placement());
+            }
+        }
+        spawns
+    }
+
+    // Returns the gravity and the limit if the search with the lock delay is needed.
+    // The gravity is the rows to fall after each input, and `u32::MAX` means 20G.
+    #[inline]
+    fn lock_delay(&self) -> Option<(u32, u32)> {
+        match (self.allow_move, self.gravity, self.move_reset_limit) {
+            // Without the gravity and the limit, it's the same as the plain softdrop.
+            (AllowMove::Softdrop, None | Some(0), None) => None,
+            (AllowMove::Softdrop, gravity, limit) => {
+                Some((gravity.unwrap_or(0), limit.unwrap_or(u32::MAX)))
+            }
+            (AllowMove::TwentyG, _, Some(limit)) => Some((u32::MAX, limit)),
+            _ => None,
         }
     }
 
     /// Collect all the placements that can be placed in the rotation system.
     /// If the placements have the same block positions, but the orientations are different, each will be collected.
     ///
-    /// Panics if the spawn is not placeable position.
+    /// The board can be any `Board<B>` whose columns implement `MoveBits` (`Board8` to `Board128`).
+    /// Narrower boards are faster, but the spawn must be inside the board and the outside of the ceiling is treated as blocks.
+    /// Panics if the spawn is not placeable position. Use `try_generate_all_moves()` to get an error instead.
     #[inline]
-    pub fn generate_all_moves(&self, board: Board64, spawn: BlPlacement) -> Vec<BlPlacement> {
-        let is_moving_in_rotation = self
+    pub fn generate_all_moves<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Vec<BlPlacement> {
+        let mut out = Vec::with_capacity(128);
+        self.generate_all_moves_into(board, spawn, &mut out);
+        out
+    }
+
+    /// Same as `generate_all_moves()`, but the placements are appended to the existing buffer.
+    /// Reusing the buffer avoids the allocation for each generation.
+    #[inline]
+    pub fn generate_all_moves_into<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+        out: &mut Vec<BlPlacement>,
+    ) {
+        let set = self.generate_all_moves_set(board, spawn);
+        self.extend_all_moves(&set, spawn, out);
+    }
+
+    // The shapes that don't move in rotation are grouped by the position, and the others are in the order of the set.
+    // It's the same order as the generation before `PlacementSet` was introduced.
+    #[inline]
+    fn extend_all_moves<B: MoveBits>(
+        &self,
+        set: &PlacementSet<B>,
+        spawn: BlPlacement,
+        out: &mut Vec<BlPlacement>,
+    ) {
+        if self
             .rotation_system
-            .is_moving_in_rotation(spawn.piece.shape);
-        match self.allow_move {
-            AllowMove::Softdrop => {
-                if is_moving_in_rotation {
-                    softdrop::moves_softdrop_with_rotation::<false>(
-                        self.rotation_system,
-                        &board,
-                        spawn,
-                    )
-                    .vec()
-                } else {
-                    softdrop::moves_softdrop_no_rotation::<false>(&board, spawn).vec()
-                }
-            }
-            AllowMove::Harddrop => {
-                if is_moving_in_rotation {
-                    harddrop::moves_harddrop_with_rotation::<false>(
-                        self.rotation_system,
-                        &board,
-                        spawn,
-                    )
-                    .vec()
-                } else {
-                    harddrop::moves_harddrop_no_rotation::<false>(&board, spawn).vec()
-                }
-            }
+            .is_moving_in_rotation(spawn.piece.shape)
+        {
+            out.extend(set);
+        } else {
+            set.extend_grouped_by_position(spawn.piece.orientation, out);
         }
     }
 
+    /// Collect all the placements that can be placed in the rotation system as a `PlacementSet`.
+    /// The result is the same as `generate_all_moves()`, but it does not allocate.
+    /// Note that the set iterates in the order of orientation, x and y,
+    /// while `generate_all_moves()` groups the orientations by the position for the shapes that don't move in rotation, such as O.
+    ///
+    /// Panics if the spawn is not placeable position.
+    #[inline]
+    pub fn generate_all_moves_set<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> PlacementSet<B> {
+        self.generate_moves_set::<_, false>(&FreeSpaceCache::new(&board), spawn)
+    }
+
     /// Collect all the placements that can be placed in the rotation system.
     /// If the placements have the same block positions, but the orientations are different, one of the placements will be collected.
     /// It is guaranteed that the placement to be collected is actually in the orientation where it can be placed.
     ///
+    /// Panics if the spawn is not placeable position. Use `try_generate_minimized_moves()` to get an error instead.
+    #[inline]
+    pub fn generate_minimized_moves<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Vec<BlPlacement> {
+        let mut out = Vec::with_capacity(128);
+        self.generate_minimized_moves_into(board, spawn, &mut out);
+        out
+    }
+
+    /// Same as `generate_minimized_moves()`, but the placements are appended to the existing buffer.
+    /// Reusing the buffer avoids the allocation for each generation.
+    #[inline]
+    pub fn generate_minimized_moves_into<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+        out: &mut Vec<BlPlacement>,
+    ) {
+        out.extend(self.generate_minimized_moves_set(board, spawn));
+    }
+
+    /// Collect all the placements that can be placed in the rotation system as a `PlacementSet`.
+    /// The result is the same as `generate_minimized_moves()`, but it does not allocate.
+    ///
     /// Panics if the spawn is not placeable position.
     #[inline]
-    pub fn generate_minimized_moves(&self, board: Board64, spawn: BlPlacement) -> Vec<BlPlacement> {
+    pub fn generate_minimized_moves_set<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> PlacementSet<B> {
+        self.generate_moves_set::<_, true>(&FreeSpaceCache::new(&board), spawn)
+    }
+
+    /// It's similar to `generate_all_moves()` except that it returns an error instead of panicking.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let spawn = piece!(TN).with(bl(4, 20));
+    /// assert_eq!(rules.try_generate_all_moves(Board64::blank(), spawn).map(|moves| moves.len()), Ok(34));
+    /// assert_eq!(rules.try_generate_all_moves(Board16::blank(), spawn), Err(MoveError::InvalidSpawn(spawn)));
+    /// assert_eq!(rules.try_generate_all_moves(Board64::filled_up_to(21), spawn), Err(MoveError::InvalidSpawn(spawn)));
+    /// ```
+    #[inline]
+    pub fn try_generate_all_moves<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<Vec<BlPlacement>, MoveError> {
+        let set = self.try_generate_all_moves_set(board, spawn)?;
+        let mut out = Vec::with_capacity(128);
+        self.extend_all_moves(&set, spawn, &mut out);
+        Ok(out)
+    }
+
+    /// It's similar to `generate_all_moves_set()` except that it returns an error instead of panicking.
+    #[inline]
+    pub fn try_generate_all_moves_set<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<PlacementSet<B>, MoveError> {
+        let cache = FreeSpaceCache::new(&board);
+        self.validate_spawn(&cache.free_space_block, spawn)?;
+        Ok(self.generate_moves_set::<_, false>(&cache, spawn))
+    }
+
+    /// It's similar to `generate_minimized_moves()` except that it returns an error instead of panicking.
+    #[inline]
+    pub fn try_generate_minimized_moves<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<Vec<BlPlacement>, MoveError> {
+        self.try_generate_minimized_moves_set(board, spawn)
+            .map(|set| set.into_iter().collect())
+    }
+
+    /// It's similar to `generate_minimized_moves_set()` except that it returns an error instead of panicking.
+    #[inline]
+    pub fn try_generate_minimized_moves_set<B: MoveBits>(
+        &self,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<PlacementSet<B>, MoveError> {
+        let cache = FreeSpaceCache::new(&board);
+        self.validate_spawn(&cache.free_space_block, spawn)?;
+        Ok(self.generate_moves_set::<_, true>(&cache, spawn))
+    }
+
+    /// Collect all the placements for each spawn on the same board, in the same way as `generate_all_moves_set()`.
+    /// The inverted board is computed once, and the free spaces of each shape are computed once and shared by the spawns of the shape.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let spawns = [piece!(TN), piece!(IN), piece!(ON)].map(|piece| piece.with(bl(4, 20)));
+    /// let sets = rules.generate_all_moves_batch(Board64::blank(), spawns);
+    /// assert_eq!(sets.map(|set| set.len()), [34, 34, 36]);
+    /// assert_eq!(sets[1], rules.generate_all_moves_set(Board64::blank(), spawns[1]));
+    /// ```
+    #[inline]
+    pub fn generate_all_moves_batch<B: MoveBits, const N: usize>(
+        &self,
+        board: Board<B>,
+        spawns: [BlPlacement; N],
+    ) -> [PlacementSet<B>; N] {
+        let cache = FreeSpaceCache::new(&board);
+        spawns.map(|spawn| self.generate_moves_set::<_, false>(&cache, spawn))
+    }
+
+    /// Collect the minimized placements for each spawn on the same board, in the same way as `generate_minimized_moves_set()`.
+    /// The inverted board is computed once, and the free spaces of each shape are computed once and shared by the spawns of the shape.
+    #[inline]
+    pub fn generate_minimized_moves_batch<B: MoveBits, const N: usize>(
+        &self,
+        board: Board<B>,
+        spawns: [BlPlacement; N],
+    ) -> [PlacementSet<B>; N] {
+        let cache = FreeSpaceCache::new(&board);
+        spawns.map(|spawn| self.generate_moves_set::<_, true>(&cache, spawn))
+    }
+
+    #[inline]
+    fn generate_moves_set<B: MoveBits, const MINIMIZE: bool>(
+        &self,
+        cache: &FreeSpaceCache<B>,
+        spawn: BlPlacement,
+    ) -> PlacementSet<B> {
+        if self.spawn_policy != SpawnPolicy::Normal {
+            let rules = MoveRules {
+                spawn_policy: SpawnPolicy::Normal,
+                ..*self
+            };
+            return self
+                .spawns_in(&cache.free_space_block, spawn)
+                .into_iter()
+                .fold(PlacementSet::blank(spawn.piece.shape), |set, spawn| {
+                    set | rules.generate_moves_set::<_, MINIMIZE>(cache, spawn)
+                });
+        }
+
         let is_moving_in_rotation = self
             .rotation_system
             .is_moving_in_rotation(spawn.piece.shape);
+
+        if let Some((gravity, limit)) = self.lock_delay() {
+            return if is_moving_in_rotation {
+                lock_delay::moves_lock_delay_with_rotation::<_, MINIMIZE>(
+                    self.rotation_system,
+                    cache,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+                .placement_set()
+            } else {
+                lock_delay::moves_lock_delay_no_rotation::<_, MINIMIZE>(
+                    self.rotation_system,
+                    cache,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+                .placement_set()
+            };
+        }
+
         match self.allow_move {
             AllowMove::Softdrop => {
                 if is_moving_in_rotation {
-                    softdrop::moves_softdrop_with_rotation::<true>(
+                    softdrop::moves_softdrop_with_rotation::<_, MINIMIZE>(
                         self.rotation_system,
-                        &board,
+                        cache,
                         spawn,
                     )
-                    .vec()
+                    .placement_set()
                 } else {
-                    softdrop::moves_softdrop_no_rotation::<true>(&board, spawn).vec()
+                    softdrop::moves_softdrop_no_rotation::<_, MINIMIZE>(cache, spawn)
+                        .placement_set()
                 }
             }
             AllowMove::Harddrop => {
                 if is_moving_in_rotation {
-                    harddrop::moves_harddrop_with_rotation::<true>(
+                    harddrop::moves_harddrop_with_rotation::<_, MINIMIZE>(
                         self.rotation_system,
-                        &board,
+                        cache,
+                        spawn,
+                    )
+                    .placement_set()
+                } else {
+                    harddrop::moves_harddrop_no_rotation::<_, MINIMIZE>(cache, spawn)
+                        .placement_set()
+                }
+            }
+            AllowMove::TwentyG => {
+                if is_moving_in_rotation {
+                    twentyg::moves_twentyg_with_rotation::<_, MINIMIZE>(
+                        self.rotation_system,
+                        cache,
                         spawn,
                     )
-                    .vec()
+                    .placement_set()
                 } else {
-                    harddrop::moves_harddrop_no_rotation::<true>(&board, spawn).vec()
+                    twentyg::moves_twentyg_no_rotation::<_, MINIMIZE>(cache, spawn).placement_set()
                 }
             }
         }
     }
 
+    #[inline]
+    fn validate_spawn<B: MoveBits>(
+        &self,
+        free_space_block: &FreeSpace<B>,
+        spawn: BlPlacement,
+    ) -> Result<(), MoveError> {
+        if self.spawns_in(free_space_block, spawn).is_empty() {
+            Err(MoveError::InvalidSpawn(spawn))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Return true when the piece can be carried to the placement.
     ///
     /// Note that the same form will succeed regardless of the orientation.
     /// If you want to be strict, use `can_reach_strictly()`.
+    ///
+    /// Panics if the shapes of the goal and the spawn are different. Use `try_can_reach()` to get an error instead.
     /// ```
     /// use std::str::FromStr;
     /// use bitris::piece;
@@ -160,16 +597,58 @@ where
     /// assert!(!srs_softdrop.can_reach_strictly(S.with(North).with(bl(2, 0)), board, spawn));
     /// assert!(!srs_harddrop.can_reach(S.with(North).with(bl(2, 0)), board, spawn));
     /// ```
-    pub fn can_reach(&self, goal: BlPlacement, board: Board64, spawn: BlPlacement) -> bool {
+    pub fn can_reach<B: MoveBits>(
+        &self,
+        goal: BlPlacement,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> bool
+    where
+        Board<B>: BoardOp,
+    {
         assert_eq!(goal.piece.shape, spawn.piece.shape);
 
         if !goal.is_in_free_space(&board) {
             return false;
         }
 
+        if self.spawn_policy != SpawnPolicy::Normal {
+            let rules = MoveRules {
+                spawn_policy: SpawnPolicy::Normal,
+                ..*self
+            };
+            return self
+                .spawns(board, spawn)
+                .into_iter()
+                .any(|spawn| rules.can_reach(goal, board, spawn));
+        }
+
         let is_moving_in_rotation = self
             .rotation_system
             .is_moving_in_rotation(spawn.piece.shape);
+
+        if let Some((gravity, limit)) = self.lock_delay() {
+            return if is_moving_in_rotation {
+                lock_delay::can_reach_lock_delay_with_rotation(
+                    self.rotation_system,
+                    goal,
+                    &board,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+            } else {
+                lock_delay::can_reach_lock_delay_no_rotation(
+                    self.rotation_system,
+                    goal,
+                    &board,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+            };
+        }
+
         match self.allow_move {
             AllowMove::Softdrop => {
                 if is_moving_in_rotation {
@@ -195,25 +674,74 @@ where
                     harddrop::can_reach_harddrop_no_rotation(goal, &board, spawn)
                 }
             }
+            AllowMove::TwentyG => {
+                if is_moving_in_rotation {
+                    twentyg::can_reach_twentyg_with_rotation(
+                        self.rotation_system,
+                        goal,
+                        &board,
+                        spawn,
+                    )
+                } else {
+                    twentyg::can_reach_twentyg_no_rotation(goal, &board, spawn)
+                }
+            }
         }
     }
 
     /// It's similar to `can_reach()` except that the orientation is strictly checked.
-    pub fn can_reach_strictly(
+    pub fn can_reach_strictly<B: MoveBits>(
         &self,
         goal: BlPlacement,
-        board: Board64,
+        board: Board<B>,
         spawn: BlPlacement,
-    ) -> bool {
+    ) -> bool
+    where
+        Board<B>: BoardOp,
+    {
         assert_eq!(goal.piece.shape, spawn.piece.shape);
 
         if !goal.is_in_free_space(&board) {
             return false;
         }
 
+        if self.spawn_policy != SpawnPolicy::Normal {
+            let rules = MoveRules {
+                spawn_policy: SpawnPolicy::Normal,
+                ..*self
+            };
+            return self
+                .spawns(board, spawn)
+                .into_iter()
+                .any(|spawn| rules.can_reach_strictly(goal, board, spawn));
+        }
+
         let is_moving_in_rotation = self
             .rotation_system
             .is_moving_in_rotation(spawn.piece.shape);
+
+        if let Some((gravity, limit)) = self.lock_delay() {
+            return if is_moving_in_rotation {
+                lock_delay::can_reach_strictly_lock_delay_with_rotation(
+                    self.rotation_system,
+                    goal,
+                    &board,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+            } else {
+                lock_delay::can_reach_lock_delay_no_rotation(
+                    self.rotation_system,
+                    goal,
+                    &board,
+                    spawn,
+                    gravity,
+                    limit,
+                )
+            };
+        }
+
         match self.allow_move {
             AllowMove::Softdrop => {
                 if is_moving_in_rotation {
@@ -239,18 +767,85 @@ where
                     harddrop::can_reach_strictly_harddrop_no_rotation(goal, &board, spawn)
                 }
             }
+            AllowMove::TwentyG => {
+                if is_moving_in_rotation {
+                    twentyg::can_reach_strictly_twentyg_with_rotation(
+                        self.rotation_system,
+                        goal,
+                        &board,
+                        spawn,
+                    )
+                } else {
+                    twentyg::can_reach_strictly_twentyg_no_rotation(goal, &board, spawn)
+                }
+            }
+        }
+    }
+
+    /// It's similar to `can_reach()` except that it returns an error instead of panicking.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let spawn = piece!(TN).with(bl(4, 20));
+    /// assert_eq!(rules.try_can_reach(piece!(TS).with(bl(0, 0)), Board64::blank(), spawn), Ok(true));
+    /// assert_eq!(
+    ///     rules.try_can_reach(piece!(SN).with(bl(0, 0)), Board64::blank(), spawn),
+    ///     Err(MoveError::ShapeMismatch { goal: Shape::S, spawn: Shape::T }),
+    /// );
+    /// ```
+    pub fn try_can_reach<B: MoveBits>(
+        &self,
+        goal: BlPlacement,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<bool, MoveError>
+    where
+        Board<B>: BoardOp,
+    {
+        self.validate_goal_and_spawn(goal, &board, spawn)?;
+        Ok(self.can_reach(goal, board, spawn))
+    }
+
+    /// It's similar to `can_reach_strictly()` except that it returns an error instead of panicking.
+    pub fn try_can_reach_strictly<B: MoveBits>(
+        &self,
+        goal: BlPlacement,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<bool, MoveError>
+    where
+        Board<B>: BoardOp,
+    {
+        self.validate_goal_and_spawn(goal, &board, spawn)?;
+        Ok(self.can_reach_strictly(goal, board, spawn))
+    }
+
+    #[inline]
+    pub(crate) fn validate_goal_and_spawn<B: MoveBits>(
+        &self,
+        goal: BlPlacement,
+        board: &Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<(), MoveError> {
+        if goal.piece.shape != spawn.piece.shape {
+            return Err(MoveError::ShapeMismatch {
+                goal: goal.piece.shape,
+                spawn: spawn.piece.shape,
+            });
         }
+        self.validate_spawn(&loaders::to_free_space_block(board), spawn)
     }
 }
 
 pub mod srs {
     use std::slice::Iter;
 
-    use crate::boards::Board64;
+    use crate::boards::{Board, BoardOp};
     use crate::coordinates::Offset;
     use crate::pieces::{Piece, Shape};
     use crate::placements::BlPlacement;
-    use crate::{AllowMove, Kick, MoveRules, Rotation, RotationSystem};
+    use crate::{AllowMove, Kick, MoveBits, MoveRules, Rotation, RotationSystem};
 
     macro_rules! k {
         ($dx: expr, $dy: expr) => {
@@ -416,9 +1011,9 @@ pub mod srs {
     /// let moves = srs::generate_all_moves(AllowMove::Softdrop, board, placement);
     /// assert_eq!(moves.len(), 34);
     /// ```
-    pub fn generate_all_moves(
+    pub fn generate_all_moves<B: MoveBits>(
         allow_move: AllowMove,
-        board: Board64,
+        board: Board<B>,
         spawn: BlPlacement,
     ) -> Vec<BlPlacement> {
         let move_rules = MoveRules::srs(allow_move);
@@ -442,9 +1037,9 @@ pub mod srs {
     /// let moves = srs::generate_minimized_moves(AllowMove::Softdrop, board, placement);
     /// assert_eq!(moves.len(), 9);
     /// ```
-    pub fn generate_minimized_moves(
+    pub fn generate_minimized_moves<B: MoveBits>(
         allow_move: AllowMove,
-        board: Board64,
+        board: Board<B>,
         spawn: BlPlacement,
     ) -> Vec<BlPlacement> {
         let move_rules = MoveRules::srs(allow_move);
@@ -456,24 +1051,30 @@ pub mod srs {
     /// Note that the same form will succeed regardless of the orientation.
     /// If you want to be strict, use `can_reach_strictly()`.
     #[inline]
-    pub fn can_reach(
+    pub fn can_reach<B: MoveBits>(
         allow_move: AllowMove,
         goal: BlPlacement,
-        board: Board64,
+        board: Board<B>,
         spawn: BlPlacement,
-    ) -> bool {
+    ) -> bool
+    where
+        Board<B>: BoardOp,
+    {
         let move_rules = MoveRules::srs(allow_move);
         move_rules.can_reach(goal, board, spawn)
     }
 
     /// It's similar to `can_reach()` except that the orientation is strictly checked.
     #[inline]
-    pub fn can_reach_strictly(
+    pub fn can_reach_strictly<B: MoveBits>(
         allow_move: AllowMove,
         goal: BlPlacement,
-        board: Board64,
+        board: Board<B>,
         spawn: BlPlacement,
-    ) -> bool {
+    ) -> bool
+    where
+        Board<B>: BoardOp,
+    {
         let move_rules = MoveRules::srs(allow_move);
         move_rules.can_reach_strictly(goal, board, spawn)
     }
@@ -561,11 +1162,728 @@ pub mod srs {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::str::FromStr;
+pub mod nrs {
+    use std::slice::Iter;
 
-    use crate::piece;
+    use crate::coordinates::{dd, Offset};
+    use crate::pieces::{Orientation, Piece, PieceBlocks, Shape};
+    use crate::{Kick, Rotation, RotationSystem};
+
+    macro_rules! blocks {
+        ($shape: ident, $orientation: ident, [$(($dx: expr, $dy: expr)),*]) => {
+            PieceBlocks::with_offsets(
+                Piece::new(Shape::$shape, Orientation::$orientation),
+                [$(dd($dx, $dy)),*],
+            )
+        };
+    }
+
+    /// Rotation system with NRS (Nintendo Rotation System) defined, as in NES Tetris.
+    ///
+    /// It's right-handed and has no kicks, so the rotation fails if the rotated piece overlaps blocks.
+    /// S, Z and I have two states, and O doesn't rotate.
+    /// The centers of rotation are defined by `piece_blocks()`,
+    /// and T, L and J spawning flat side up are `Orientation::South` as the forms are the same as SRS.
+    ///
+    /// Hold is not part of the rotation system, so simply don't hold in the order to follow NES.
+    /// See also `GameRules::nes()`.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// // I rotates around the third block from the left.
+    /// let placement = piece!(IN).with(bl(3, 10)).to_cc_placement_in(&NrsKickTable);
+    /// let result = NrsKickTable.test_kick(&Board64::blank(), placement, Rotation::Cw).unwrap();
+    /// assert_eq!(result.destination.to_bl_placement_in(&NrsKickTable), piece!(IE).with(bl(5, 9)));
+    /// ```
+    #[derive(Copy, Clone, Hash, Debug, Default)]
+    pub struct NrsKickTable;
+
+    impl NrsKickTable {
+        const NO_KICK: [Kick; 1] = [Kick::new(Offset::new(0, 0))];
+
+        const PIECE_BLOCKS: [PieceBlocks; 4 * 7] = [
+            blocks!(T, North, [(-1, 0), (0, 0), (1, 0), (0, 1)]),
+            blocks!(T, East, [(0, 1), (0, 0), (0, -1), (1, 0)]),
+            blocks!(T, South, [(1, 0), (0, 0), (-1, 0), (0, -1)]),
+            blocks!(T, West, [(0, -1), (0, 0), (0, 1), (-1, 0)]),
+            blocks!(I, North, [(-2, 0), (-1, 0), (0, 0), (1, 0)]),
+            blocks!(I, East, [(0, 2), (0, 1), (0, 0), (0, -1)]),
+            blocks!(I, South, [(-2, 0), (-1, 0), (0, 0), (1, 0)]),
+            blocks!(I, West, [(0, 2), (0, 1), (0, 0), (0, -1)]),
+            blocks!(O, North, [(-1, 0), (0, 0), (-1, -1), (0, -1)]),
+            blocks!(O, East, [(-1, 0), (0, 0), (-1, -1), (0, -1)]),
+            blocks!(O, South, [(-1, 0), (0, 0), (-1, -1), (0, -1)]),
+            blocks!(O, West, [(-1, 0), (0, 0), (-1, -1), (0, -1)]),
+            blocks!(L, North, [(-1, 0), (0, 0), (1, 0), (1, 1)]),
+            blocks!(L, East, [(0, 1), (0, 0), (0, -1), (1, -1)]),
+            blocks!(L, South, [(1, 0), (0, 0), (-1, 0), (-1, -1)]),
+            blocks!(L, West, [(0, -1), (0, 0), (0, 1), (-1, 1)]),
+            blocks!(J, North, [(-1, 1), (-1, 0), (0, 0), (1, 0)]),
+            blocks!(J, East, [(1, 1), (0, 1), (0, 0), (0, -1)]),
+            blocks!(J, South, [(1, -1), (1, 0), (0, 0), (-1, 0)]),
+            blocks!(J, West, [(-1, -1), (0, -1), (0, 0), (0, 1)]),
+            blocks!(S, North, [(0, 0), (1, 0), (-1, -1), (0, -1)]),
+            blocks!(S, East, [(0, 1), (0, 0), (1, 0), (1, -1)]),
+            blocks!(S, South, [(0, 0), (1, 0), (-1, -1), (0, -1)]),
+            blocks!(S, West, [(0, 1), (0, 0), (1, 0), (1, -1)]),
+            blocks!(Z, North, [(-1, 0), (0, 0), (0, -1), (1, -1)]),
+            blocks!(Z, East, [(1, 1), (0, 0), (1, 0), (0, -1)]),
+            blocks!(Z, South, [(-1, 0), (0, 0), (0, -1), (1, -1)]),
+            blocks!(Z, West, [(1, 1), (0, 0), (1, 0), (0, -1)]),
+        ];
+    }
+
+    impl RotationSystem for NrsKickTable {
+        fn iter_kicks(&self, _: Piece, rotation: Rotation) -> Iter<'_, Kick> {
+            assert_ne!(
+                rotation,
+                Rotation::R180,
+                "This kick table does not support 180 rotation."
+            );
+            Self::NO_KICK.iter()
+        }
+
+        fn is_moving_in_rotation(&self, shape: Shape) -> bool {
+            shape != Shape::O
+        }
+
+        fn piece_blocks(&self, piece: Piece) -> &PieceBlocks {
+            &Self::PIECE_BLOCKS[piece.shape as usize * 4 + piece.orientation as usize]
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::str::FromStr;
+
+        use crate::nrs::*;
+        use crate::piece;
+        use crate::prelude::*;
+
+        #[test]
+        fn piece_blocks_have_same_forms() {
+            for piece in Piece::all_iter() {
+                let blocks = NrsKickTable.piece_blocks(piece);
+                assert_eq!(blocks.piece, piece);
+                assert!(blocks.has_same_form_as(piece.to_piece_blocks()));
+            }
+        }
+
+        #[test]
+        fn rotate_without_kicks() {
+            let board = Board64::blank();
+
+            // Two states go back and forth.
+            for shape in [Shape::S, Shape::Z, Shape::I] {
+                let spawn = shape.with(Orientation::North).with(bl(3, 10));
+                let mut placement = spawn.to_cc_placement_in(&NrsKickTable);
+                let mut forms = Vec::new();
+                for _ in 0..4 {
+                    placement = NrsKickTable
+                        .test_kick(&board, placement, Rotation::Cw)
+                        .unwrap()
+                        .destination;
+                    forms.push(
+                        placement
+                            .to_bl_placement_in(&NrsKickTable)
+                            .canonical_or_self(),
+                    );
+                }
+                assert_eq!(forms[0], forms[2]);
+                assert_eq!(forms[1], forms[3]);
+                assert_eq!(forms[1], spawn);
+            }
+
+            // O doesn't move.
+            let placement = piece!(ON).with(bl(4, 10));
+            let result = NrsKickTable
+                .test_kick(
+                    &board,
+                    placement.to_cc_placement_in(&NrsKickTable),
+                    Rotation::Ccw,
+                )
+                .unwrap();
+            assert_eq!(
+                result
+                    .destination
+                    .to_bl_placement_in(&NrsKickTable)
+                    .canonical_or_self(),
+                placement
+            );
+
+            // No kicks by the wall.
+            let placement = piece!(IE).with(bl(9, 10)).to_cc_placement_in(&NrsKickTable);
+            assert_eq!(
+                NrsKickTable.test_kick(&board, placement, Rotation::Cw),
+                None
+            );
+        }
+
+        #[test]
+        fn generate_moves() {
+            let rules = MoveRules::nrs(AllowMove::Softdrop);
+            let game_rules = GameRules::nes();
+
+            let board = Board64::blank();
+            for shape in Shape::all_iter() {
+                let spawn = game_rules.spawn(shape);
+                assert_eq!(
+                    rules.generate_minimized_moves(board, spawn),
+                    MoveRules::srs(AllowMove::Softdrop).generate_minimized_moves(board, spawn),
+                );
+            }
+
+            let board = Board64::from_str(
+                "\
+                XXX.......\
+                XX........\
+                XX.XXXXXXX\
+                XX..XXXXXX\
+                XX.XXXXXXX\
+                ",
+            )
+            .unwrap();
+            let spawn = game_rules.spawn(Shape::T);
+            assert!(!rules.can_reach(piece!(TE).with(bl(2, 0)), board, spawn));
+            assert!(rules.can_reach(piece!(TS).with(bl(3, 5)), board, spawn));
+
+            // A tuck under the overhang.
+            let spawn = game_rules.spawn(Shape::I);
+            assert!(rules.can_reach(piece!(IN).with(bl(2, 3)), board, spawn));
+            assert!(!MoveRules::nrs(AllowMove::Harddrop).can_reach(
+                piece!(IN).with(bl(2, 3)),
+                board,
+                spawn
+            ));
+        }
+    }
+}
+
+pub mod ars {
+    use std::slice::Iter;
+
+    use crate::coordinates::{Location, Offset};
+    use crate::pieces::{Piece, Shape};
+    use crate::placements::CcPlacement;
+    use crate::{Kick, Rotate, Rotation, RotationSystem, TestKickResult};
+
+    macro_rules! k {
+        ($dx: expr, $dy: expr) => {
+            Kick::new(Offset::new($dx, $dy))
+        };
+    }
+
+    /// Kick table with ARS (Arika Rotation System) defined.
+    ///
+    /// The forms of the pieces are the same as SRS, and the kicks include the difference of the rotation centers.
+    /// The orientations are also the same as SRS, so T, L and J spawning flat side up in ARS are `Orientation::South`.
+    ///
+    /// The first kick is the basic rotation, then 1 right and 1 left are tested.
+    /// L, J and T cannot kick if the first cell blocking the basic rotation is in the center column (reading from the top-left).
+    /// So the kicks depend on the blocks, and `test_kick()` applies the rules instead of only testing `iter_kicks()`.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let board = Board64::from_str("
+    ///     ....#.....
+    ///     ..........
+    ///     ..........
+    /// ").unwrap();
+    ///
+    /// // The center column is blocked.
+    /// let placement = piece!(LS).with(cc(4, 1));
+    /// assert_eq!(ArsKickTable::TGM2.test_kick(&board, placement, Rotation::Cw), None);
+    /// // SRS can rotate by kicks.
+    /// assert!(SrsKickTable.test_kick(&board, placement, Rotation::Cw).is_some());
+    /// ```
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+    pub struct ArsKickTable {
+        /// If true, T and I can kick upward on the ground (floor kicks), and I can also kick by walls as in TGM3.
+        /// I tests 1 and 2 up when it becomes vertical, and 1 right, 2 right and 1 left when it becomes horizontal.
+        /// T tests 1 up after the wall kicks.
+        /// Note that the number of floor kicks is not limited.
+        pub floor_kick: bool,
+    }
+
+    impl ArsKickTable {
+        /// The rules in TGM and TGM2. I cannot kick.
+        pub const TGM2: Self = Self { floor_kick: false };
+
+        /// The rules in TGM3 (Classic) with floor kicks.
+        pub const TGM3: Self = Self { floor_kick: true };
+
+        const LJT_NE: [Kick; 3] = [k!(0, 1), k!(1, 1), k!(-1, 1)];
+        const LJT_NW: [Kick; 3] = [k!(0, 1), k!(1, 1), k!(-1, 1)];
+        const LJT_ES: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const LJT_EN: [Kick; 3] = [k!(0, -1), k!(1, -1), k!(-1, -1)];
+        const LJT_SW: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const LJT_SE: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const LJT_WN: [Kick; 3] = [k!(0, -1), k!(1, -1), k!(-1, -1)];
+        const LJT_WS: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+
+        const T_FLOOR_NE: [Kick; 4] = [k!(0, 1), k!(1, 1), k!(-1, 1), k!(0, 2)];
+        const T_FLOOR_NW: [Kick; 4] = [k!(0, 1), k!(1, 1), k!(-1, 1), k!(0, 2)];
+        const T_FLOOR_ES: [Kick; 4] = [k!(0, 0), k!(1, 0), k!(-1, 0), k!(0, 1)];
+        const T_FLOOR_EN: [Kick; 4] = [k!(0, -1), k!(1, -1), k!(-1, -1), k!(0, 0)];
+        const T_FLOOR_SW: [Kick; 4] = [k!(0, 0), k!(1, 0), k!(-1, 0), k!(0, 1)];
+        const T_FLOOR_SE: [Kick; 4] = [k!(0, 0), k!(1, 0), k!(-1, 0), k!(0, 1)];
+        const T_FLOOR_WN: [Kick; 4] = [k!(0, -1), k!(1, -1), k!(-1, -1), k!(0, 0)];
+        const T_FLOOR_WS: [Kick; 4] = [k!(0, 0), k!(1, 0), k!(-1, 0), k!(0, 1)];
+
+        const I_NE: [Kick; 1] = [k!(1, 0)];
+        const I_NW: [Kick; 1] = [k!(1, -1)];
+        const I_ES: [Kick; 1] = [k!(0, 0)];
+        const I_EN: [Kick; 1] = [k!(-1, 0)];
+        const I_SW: [Kick; 1] = [k!(0, -1)];
+        const I_SE: [Kick; 1] = [k!(0, 0)];
+        const I_WN: [Kick; 1] = [k!(-1, 1)];
+        const I_WS: [Kick; 1] = [k!(0, 1)];
+
+        const I_FLOOR_NE: [Kick; 3] = [k!(1, 0), k!(1, 1), k!(1, 2)];
+        const I_FLOOR_NW: [Kick; 3] = [k!(1, -1), k!(1, 0), k!(1, 1)];
+        const I_FLOOR_ES: [Kick; 4] = [k!(0, 0), k!(1, 0), k!(2, 0), k!(-1, 0)];
+        const I_FLOOR_EN: [Kick; 4] = [k!(-1, 0), k!(0, 0), k!(1, 0), k!(-2, 0)];
+        const I_FLOOR_SW: [Kick; 3] = [k!(0, -1), k!(0, 0), k!(0, 1)];
+        const I_FLOOR_SE: [Kick; 3] = [k!(0, 0), k!(0, 1), k!(0, 2)];
+        const I_FLOOR_WN: [Kick; 4] = [k!(-1, 1), k!(0, 1), k!(1, 1), k!(-2, 1)];
+        const I_FLOOR_WS: [Kick; 4] = [k!(0, 1), k!(1, 1), k!(2, 1), k!(-1, 1)];
+
+        const S_NE: [Kick; 3] = [k!(-1, 1), k!(0, 1), k!(-2, 1)];
+        const S_NW: [Kick; 3] = [k!(0, 1), k!(1, 1), k!(-1, 1)];
+        const S_ES: [Kick; 3] = [k!(1, 0), k!(2, 0), k!(0, 0)];
+        const S_EN: [Kick; 3] = [k!(1, -1), k!(2, -1), k!(0, -1)];
+        const S_SW: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const S_SE: [Kick; 3] = [k!(-1, 0), k!(0, 0), k!(-2, 0)];
+        const S_WN: [Kick; 3] = [k!(0, -1), k!(1, -1), k!(-1, -1)];
+        const S_WS: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+
+        const Z_NE: [Kick; 3] = [k!(0, 1), k!(1, 1), k!(-1, 1)];
+        const Z_NW: [Kick; 3] = [k!(1, 1), k!(2, 1), k!(0, 1)];
+        const Z_ES: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const Z_EN: [Kick; 3] = [k!(0, -1), k!(1, -1), k!(-1, -1)];
+        const Z_SW: [Kick; 3] = [k!(1, 0), k!(2, 0), k!(0, 0)];
+        const Z_SE: [Kick; 3] = [k!(0, 0), k!(1, 0), k!(-1, 0)];
+        const Z_WN: [Kick; 3] = [k!(-1, -1), k!(0, -1), k!(-2, -1)];
+        const Z_WS: [Kick; 3] = [k!(-1, 0), k!(0, 0), k!(-2, 0)];
+
+        const O_NE: [Kick; 1] = [k!(0, 1)];
+        const O_NW: [Kick; 1] = [k!(1, 0)];
+        const O_ES: [Kick; 1] = [k!(1, 0)];
+        const O_EN: [Kick; 1] = [k!(0, -1)];
+        const O_SW: [Kick; 1] = [k!(0, -1)];
+        const O_SE: [Kick; 1] = [k!(-1, 0)];
+        const O_WN: [Kick; 1] = [k!(-1, 0)];
+        const O_WS: [Kick; 1] = [k!(0, 1)];
+
+        const EMPTY: [Kick; 0] = [];
+
+        const LJT_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::LJT_NE,
+            &Self::LJT_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::LJT_ES,
+            &Self::LJT_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::LJT_SW,
+            &Self::LJT_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::LJT_WN,
+            &Self::LJT_WS,
+            &Self::EMPTY,
+        ];
+        const T_FLOOR_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::T_FLOOR_NE,
+            &Self::T_FLOOR_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::T_FLOOR_ES,
+            &Self::T_FLOOR_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::T_FLOOR_SW,
+            &Self::T_FLOOR_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::T_FLOOR_WN,
+            &Self::T_FLOOR_WS,
+            &Self::EMPTY,
+        ];
+        const I_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::I_NE,
+            &Self::I_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::I_ES,
+            &Self::I_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::I_SW,
+            &Self::I_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::I_WN,
+            &Self::I_WS,
+            &Self::EMPTY,
+        ];
+        const I_FLOOR_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::I_FLOOR_NE,
+            &Self::I_FLOOR_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::I_FLOOR_ES,
+            &Self::I_FLOOR_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::I_FLOOR_SW,
+            &Self::I_FLOOR_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::I_FLOOR_WN,
+            &Self::I_FLOOR_WS,
+            &Self::EMPTY,
+        ];
+        const S_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::S_NE,
+            &Self::S_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::S_ES,
+            &Self::S_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::S_SW,
+            &Self::S_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::S_WN,
+            &Self::S_WS,
+            &Self::EMPTY,
+        ];
+        const Z_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::Z_NE,
+            &Self::Z_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::Z_ES,
+            &Self::Z_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::Z_SW,
+            &Self::Z_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::Z_WN,
+            &Self::Z_WS,
+            &Self::EMPTY,
+        ];
+        const O_KICKS: [&'static [Kick]; 12] = [
+            // from North
+            &Self::O_NE,
+            &Self::O_NW,
+            &Self::EMPTY,
+            // from East
+            &Self::O_ES,
+            &Self::O_EN,
+            &Self::EMPTY,
+            // from South
+            &Self::O_SW,
+            &Self::O_SE,
+            &Self::EMPTY,
+            // from West
+            &Self::O_WN,
+            &Self::O_WS,
+            &Self::EMPTY,
+        ];
+
+        // Returns true if the first blocked cell in the reading order from the top-left is in the center column.
+        // The center column of the 3x3 box is always the x of the center of the piece.
+        fn is_blocked_in_center_column(
+            is_free: &impl Fn(Location) -> bool,
+            placement: CcPlacement,
+        ) -> bool {
+            if !matches!(placement.piece.shape, Shape::L | Shape::J | Shape::T) {
+                return false;
+            }
+
+            let mut locations = placement.locations();
+            locations.sort_by_key(|location| (-location.y, location.x));
+            locations
+                .into_iter()
+                .find(|&location| !is_free(location))
+                .is_some_and(|location| location.x == placement.position.cx)
+        }
+    }
+
+    impl RotationSystem for ArsKickTable {
+        fn iter_kicks(&self, piece: Piece, rotation: Rotation) -> Iter<'_, Kick> {
+            assert_ne!(
+                rotation,
+                Rotation::R180,
+                "This kick table does not support 180 rotation."
+            );
+            let index = piece.orientation as usize * 3 + rotation as usize;
+            match piece.shape {
+                Shape::L | Shape::J => Self::LJT_KICKS[index].iter(),
+                Shape::T if self.floor_kick => Self::T_FLOOR_KICKS[index].iter(),
+                Shape::T => Self::LJT_KICKS[index].iter(),
+                Shape::I if self.floor_kick => Self::I_FLOOR_KICKS[index].iter(),
+                Shape::I => Self::I_KICKS[index].iter(),
+                Shape::S => Self::S_KICKS[index].iter(),
+                Shape::Z => Self::Z_KICKS[index].iter(),
+                Shape::O => Self::O_KICKS[index].iter(),
+            }
+        }
+
+        fn is_moving_in_rotation(&self, shape: Shape) -> bool {
+            shape != Shape::O
+        }
+
+        fn has_static_kicks(&self) -> bool {
+            false
+        }
+
+        fn test_kick_with(
+            &self,
+            is_free: impl Fn(Location) -> bool,
+            placement: CcPlacement,
+            rotation: Rotation,
+        ) -> Option<TestKickResult> {
+            let to = placement.rotate(rotation);
+            let kicks = self.iter_kicks(placement.piece, rotation).as_slice();
+            let basic = kicks[0];
+
+            let is_grounded = || {
+                let below = placement + Offset::new(0, -1);
+                !below.locations().into_iter().all(&is_free)
+            };
+
+            for (test_index, kick) in kicks.iter().enumerate() {
+                // Kicks upward than the basic rotation are floor kicks.
+                if basic.offset.dy < kick.offset.dy && !is_grounded() {
+                    continue;
+                }
+
+                let destination = to + kick.offset;
+                if destination.locations().into_iter().all(&is_free) {
+                    return Some(TestKickResult {
+                        test_index,
+                        kick: *kick,
+                        destination,
+                    });
+                }
+
+                if test_index == 0 && Self::is_blocked_in_center_column(&is_free, destination) {
+                    return None;
+                }
+            }
+
+            None
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::str::FromStr;
+
+        use crate::ars::*;
+        use crate::piece;
+        use crate::prelude::*;
+
+        fn sorted(mut locations: [Location; 4]) -> [Location; 4] {
+            locations.sort_by_key(|location| (location.x, location.y));
+            locations
+        }
+
+        #[test]
+        fn rotate_in_the_box() {
+            let board = Board64::blank();
+            let rules = ArsKickTable::TGM2;
+
+            // T rotates around the bottom of the 3x3 box.
+            let spawn = piece!(TS).with(cc(4, 5));
+            let result = rules.test_kick(&board, spawn, Rotation::Cw).unwrap();
+            assert_eq!(result.test_index, 0);
+            assert_eq!(
+                sorted(result.destination.locations()),
+                sorted([xy(4, 6), xy(3, 5), xy(4, 5), xy(4, 4)]),
+            );
+            let result = rules
+                .test_kick(&board, result.destination, Rotation::Cw)
+                .unwrap();
+            assert_eq!(
+                sorted(result.destination.locations()),
+                sorted([xy(4, 5), xy(3, 4), xy(4, 4), xy(5, 4)]),
+            );
+
+            // The form of S and Z moves to the left or right column when it becomes vertical.
+            let spawn = piece!(SN).with(bl(3, 4));
+            let result = rules.test_kick(&board, spawn, Rotation::Cw).unwrap();
+            assert_eq!(
+                result.destination.to_bl_placement(),
+                piece!(SE).with(bl(3, 4))
+            );
+            let spawn = piece!(ZN).with(bl(3, 4));
+            let result = rules.test_kick(&board, spawn, Rotation::Cw).unwrap();
+            assert_eq!(
+                result.destination.to_bl_placement(),
+                piece!(ZE).with(bl(4, 4))
+            );
+
+            // Rotations return to the same placement.
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(3, 10));
+                let mut placement = spawn.to_cc_placement();
+                for _ in 0..4 {
+                    placement = rules
+                        .test_kick(&board, placement, Rotation::Cw)
+                        .unwrap()
+                        .destination;
+                }
+                assert_eq!(placement.to_bl_placement(), spawn);
+            }
+        }
+
+        #[test]
+        fn center_column_rule() {
+            let placement = piece!(LS).with(cc(4, 1));
+            let board = Board64::from_str(
+                "\
+                ...#......\
+                ..........\
+                ..........\
+                ",
+            )
+            .unwrap();
+            let result = ArsKickTable::TGM2.test_kick(&board, placement, Rotation::Cw);
+            assert_eq!(result.map(|it| it.test_index), Some(1));
+
+            let board = Board64::from_str(
+                "\
+                ....#.....\
+                ..........\
+                ..........\
+                ",
+            )
+            .unwrap();
+            assert_eq!(
+                ArsKickTable::TGM2.test_kick(&board, placement, Rotation::Cw),
+                None
+            );
+
+            // The rule is not applied to the cells outside the center column.
+            let board = Board64::from_str(
+                "\
+                ..........\
+                ..........\
+                .....#....\
+                ",
+            )
+            .unwrap();
+            let result =
+                ArsKickTable::TGM2.test_kick(&board, piece!(TW).with(cc(4, 1)), Rotation::Cw);
+            assert_eq!(
+                result.map(|it| it.destination.to_bl_placement()),
+                Some(piece!(TN).with(bl(2, 0))),
+            );
+        }
+
+        #[test]
+        fn floor_kick() {
+            let board = Board64::blank();
+            let placement = piece!(IN).with(bl(0, 0));
+            assert_eq!(
+                ArsKickTable::TGM2.test_kick(&board, placement, Rotation::Cw),
+                None
+            );
+
+            let result = ArsKickTable::TGM3
+                .test_kick(&board, placement, Rotation::Cw)
+                .unwrap();
+            assert_eq!(result.test_index, 2);
+            assert_eq!(
+                result.destination.to_bl_placement(),
+                piece!(IE).with(bl(2, 0))
+            );
+
+            // Floor kicks are available only on the ground.
+            let board = Board64::from_str(
+                "\
+                #.........\
+                ..........\
+                ..........\
+                ",
+            )
+            .unwrap();
+            let placement = piece!(IN).with(bl(1, 1));
+            assert_eq!(
+                ArsKickTable::TGM3.test_kick(&board, placement, Rotation::Cw),
+                None
+            );
+        }
+
+        #[test]
+        fn generate_moves() {
+            let rules = MoveRules::ars(AllowMove::Softdrop);
+            let srs_rules = MoveRules::srs(AllowMove::Softdrop);
+            let board = Board64::blank();
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(3, 20));
+                assert_eq!(
+                    rules.generate_minimized_moves(board, spawn),
+                    srs_rules.generate_minimized_moves(board, spawn),
+                );
+            }
+
+            // ARS has no kicks downward.
+            let board = Board64::from_str(
+                "\
+                XXX.......\
+                XX........\
+                XX.XXXXXXX\
+                XX..XXXXXX\
+                XX.XXXXXXX\
+                ",
+            )
+            .unwrap();
+            let spawn = piece!(TS).with(bl(3, 20));
+            let goal = piece!(TE).with(bl(2, 0));
+            assert!(srs_rules.can_reach(goal, board, spawn));
+            assert!(!rules.can_reach(goal, board, spawn));
+            assert!(!rules.generate_minimized_moves(board, spawn).contains(&goal));
+
+            for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+                let rules = MoveRules::ars(allow_move);
+                let limited = rules.with_move_reset_limit(1000);
+                for shape in Shape::all_iter() {
+                    let spawn = shape.with(Orientation::North).with(bl(3, 20));
+                    let moves = rules.generate_all_moves(board, spawn);
+                    assert!(moves
+                        .iter()
+                        .all(|&goal| rules.can_reach_strictly(goal, board, spawn)));
+                    if allow_move != AllowMove::Harddrop {
+                        assert_eq!(limited.generate_all_moves(board, spawn), moves);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::piece;
     use crate::prelude::*;
 
     #[test]
@@ -692,4 +2010,602 @@ mod tests {
         assert!(!srs_softdrop.can_reach(piece!(ON).with(bl(1, 0)), board, spawn));
         assert!(!srs_softdrop.can_reach(piece!(ON).with(bl(7, 0)), board, spawn));
     }
+
+    #[test]
+    fn generate_moves_board128() {
+        let board = Board64::from_str(
+            " \
+            ..XXXXXX..\
+            ..........\
+            X.......XX\
+            XX..XXXXXX\
+        ",
+        )
+        .unwrap();
+        let board128 = Board128::from(board);
+
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let rules = MoveRules::srs(allow_move);
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                assert_eq!(
+                    rules.generate_all_moves(board128, spawn),
+                    rules.generate_all_moves(board, spawn),
+                );
+                assert_eq!(
+                    rules.generate_minimized_moves(board128, spawn),
+                    rules.generate_minimized_moves(board, spawn),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generate_moves_over_64_rows() {
+        // A tall stack that leaves only a 2-wide well at the bottom.
+        let mut board = Board128::blank();
+        for y in 0..100 {
+            for x in 2..10 {
+                board.set_at(xy(x, y));
+            }
+        }
+
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let spawn = piece!(ON).with(bl(4, 110));
+        let moves = rules.generate_minimized_moves(board, spawn);
+        assert_eq!(moves.len(), 9);
+        assert!(moves.contains(&piece!(ON).with(bl(0, 0))));
+        assert!(moves.contains(&piece!(ON).with(bl(8, 100))));
+
+        assert!(rules.can_reach(piece!(ON).with(bl(0, 0)), board, spawn));
+        assert!(!rules.can_reach(piece!(ON).with(bl(2, 0)), board, spawn));
+
+        let spawn = piece!(IN).with(bl(3, 110));
+        assert!(rules.can_reach_strictly(piece!(IE).with(bl(1, 0)), board, spawn));
+    }
+
+    #[test]
+    fn generate_moves_small_boards() {
+        let board = Board64::from_str(
+            " \
+            ..........\
+            ..........\
+            ##.....###\
+            ##....####\
+            ##...#####\
+            ##....####\
+        ",
+        )
+        .unwrap();
+        let board8 = Board8::shrink_from(board);
+        let board16 = Board16::shrink_from(board);
+        let board32 = Board32::shrink_from(board);
+
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let rules = MoveRules::srs(allow_move);
+            for shape in Shape::all_iter() {
+                // The spawn must be inside the board.
+                let spawn = shape.with(Orientation::North).with(cc(4, 6));
+                let expected = rules.generate_all_moves(board, spawn.into());
+                assert_eq!(rules.generate_all_moves(board8, spawn.into()), expected);
+                assert_eq!(rules.generate_all_moves(board16, spawn.into()), expected);
+                assert_eq!(rules.generate_all_moves(board32, spawn.into()), expected);
+
+                let expected = rules.generate_minimized_moves(board, spawn.into());
+                assert_eq!(
+                    rules.generate_minimized_moves(board8, spawn.into()),
+                    expected
+                );
+                assert_eq!(
+                    rules.generate_minimized_moves(board16, spawn.into()),
+                    expected
+                );
+                assert_eq!(
+                    rules.generate_minimized_moves(board32, spawn.into()),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn try_generate_moves() {
+        let board = Board8::from_str(
+            "\
+            ....#.....\
+            ..........\
+            ",
+        )
+        .unwrap();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+
+        let spawn = piece!(TN).with(bl(3, 6));
+        assert_eq!(
+            rules.try_generate_minimized_moves(board, spawn),
+            Ok(rules.generate_minimized_moves(board, spawn))
+        );
+        assert_eq!(
+            rules.try_generate_all_moves_set(board, spawn),
+            Ok(rules.generate_all_moves_set(board, spawn))
+        );
+
+        // Overlaps blocks, or outside the board
+        for spawn in [
+            piece!(TN).with(bl(3, 1)),
+            piece!(TN).with(bl(3, 7)),
+            piece!(TN).with(bl(8, 3)),
+            piece!(TN).with(bl(-1, 3)),
+        ] {
+            assert_eq!(
+                rules.try_generate_all_moves(board, spawn),
+                Err(MoveError::InvalidSpawn(spawn))
+            );
+            assert_eq!(
+                rules.try_generate_minimized_moves_set(board, spawn),
+                Err(MoveError::InvalidSpawn(spawn))
+            );
+            assert_eq!(
+                rules.try_can_reach(piece!(TN).with(bl(0, 0)), board, spawn),
+                Err(MoveError::InvalidSpawn(spawn))
+            );
+        }
+
+        let spawn = piece!(TN).with(bl(3, 6));
+        assert_eq!(
+            rules.try_can_reach_strictly(piece!(TN).with(bl(0, 0)), board, spawn),
+            Ok(true)
+        );
+        assert_eq!(
+            rules.try_can_reach_strictly(piece!(IN).with(bl(0, 0)), board, spawn),
+            Err(MoveError::ShapeMismatch {
+                goal: Shape::I,
+                spawn: Shape::T
+            })
+        );
+    }
+
+    #[test]
+    fn generate_moves_twentyg_on_blank() {
+        let board = Board64::blank();
+        let softdrop = MoveRules::srs(AllowMove::Softdrop);
+        let twentyg = MoveRules::srs(AllowMove::TwentyG);
+        for shape in Shape::all_iter() {
+            let spawn = shape.with(Orientation::North).with(bl(4, 20));
+            assert_eq!(
+                twentyg.generate_all_moves_set(board, spawn),
+                softdrop.generate_all_moves_set(board, spawn),
+            );
+            assert_eq!(
+                twentyg.generate_minimized_moves_set(board, spawn),
+                softdrop.generate_minimized_moves_set(board, spawn),
+            );
+        }
+    }
+
+    #[test]
+    fn generate_moves_twentyg_cannot_climb() {
+        let board = Board64::from_str(
+            "\
+            ..#.......\
+            ..#.......\
+            ..#.......\
+            ..#.......\
+            ",
+        )
+        .unwrap();
+        let softdrop = MoveRules::srs(AllowMove::Softdrop);
+        let twentyg = MoveRules::srs(AllowMove::TwentyG);
+
+        let spawn = piece!(ON).with(bl(4, 20));
+        assert_eq!(softdrop.generate_minimized_moves(board, spawn).len(), 9);
+        assert_eq!(twentyg.generate_minimized_moves(board, spawn).len(), 6);
+        assert!(softdrop.can_reach(piece!(ON).with(bl(0, 0)), board, spawn));
+        assert!(!twentyg.can_reach(piece!(ON).with(bl(0, 0)), board, spawn));
+        assert!(twentyg.can_reach(piece!(OS).with(bl(8, 0)), board, spawn));
+
+        for shape in Shape::all_iter() {
+            let spawn = shape.with(Orientation::North).with(bl(4, 20));
+            let softdrop_set = softdrop.generate_all_moves_set(board, spawn);
+            let twentyg_set = twentyg.generate_all_moves_set(board, spawn);
+            assert!((twentyg_set - softdrop_set).is_empty());
+            assert!(twentyg_set
+                .iter()
+                .all(|placement| 3 <= placement.position.lx && placement.is_landing(&board)));
+        }
+    }
+
+    #[test]
+    fn generate_moves_twentyg_tuck() {
+        let board = Board64::from_str(
+            "\
+            ....######\
+            ..........\
+            ",
+        )
+        .unwrap();
+        let spawn = piece!(IN).with(bl(0, 20));
+        let goal = piece!(IN).with(bl(6, 0));
+
+        let twentyg = MoveRules::srs(AllowMove::TwentyG);
+        assert!(twentyg.can_reach(goal, board, spawn));
+        assert!(twentyg.can_reach_strictly(goal, board, spawn));
+        assert!(twentyg
+            .generate_minimized_moves(board, spawn)
+            .contains(&goal));
+        assert!(!MoveRules::srs(AllowMove::Harddrop).can_reach(goal, board, spawn));
+
+        // O cannot climb onto the overhang because it's dropped to the ground immediately.
+        let spawn = piece!(ON).with(bl(0, 20));
+        let goal = piece!(ON).with(bl(6, 2));
+        assert!(!twentyg.can_reach(goal, board, spawn));
+        assert!(MoveRules::srs(AllowMove::Softdrop).can_reach(goal, board, spawn));
+    }
+
+    #[test]
+    fn generate_moves_with_enough_move_reset_limit() {
+        let board = Board64::from_str(
+            "\
+            ..........\
+            ....##....\
+            #.....####\
+            ##...#####\
+            ##....####\
+            ",
+        )
+        .unwrap();
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let rules = MoveRules::srs(allow_move);
+            let limited = rules.with_move_reset_limit(1000);
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                assert_eq!(
+                    limited.generate_all_moves_set(board, spawn),
+                    rules.generate_all_moves_set(board, spawn),
+                );
+                assert_eq!(
+                    limited.generate_minimized_moves_set(board, spawn),
+                    rules.generate_minimized_moves_set(board, spawn),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generate_moves_with_gravity() {
+        let board = Board64::from_str(
+            "\
+            ..........\
+            ....##....\
+            #.....####\
+            ##...#####\
+            ##....####\
+            ",
+        )
+        .unwrap();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let twenty_g = MoveRules::srs(AllowMove::TwentyG);
+        for shape in Shape::all_iter() {
+            let spawn = shape.with(Orientation::North).with(bl(4, 20));
+
+            // No gravity is the same as the soft drop, and the gravity over the board is the same as 20G.
+            assert_eq!(
+                rules.with_gravity(0).generate_all_moves_set(board, spawn),
+                rules.generate_all_moves_set(board, spawn),
+            );
+            assert_eq!(
+                rules.with_gravity(64).generate_all_moves_set(board, spawn),
+                twenty_g.generate_all_moves_set(board, spawn),
+            );
+
+            // The slower gravity reaches more.
+            let moves = [0, 1, 2, 4, 64].map(|rows| {
+                rules
+                    .with_gravity(rows)
+                    .generate_minimized_moves_set(board, spawn)
+            });
+            for pair in moves.windows(2) {
+                assert_eq!(pair[0] | pair[1], pair[0]);
+            }
+            assert_eq!(
+                moves[1],
+                rules
+                    .with_gravity(1)
+                    .with_move_reset_limit(1000)
+                    .generate_minimized_moves_set(board, spawn),
+            );
+        }
+    }
+
+    #[test]
+    fn can_reach_with_move_reset_limit() {
+        let board = Board64::from_str(
+            "\
+            ###.......\
+            ##........\
+            ##.#######\
+            ##..######\
+            ##.#######\
+            ",
+        )
+        .unwrap();
+        let spawn = piece!(TN).with(bl(4, 20));
+        let goal = piece!(TE).with(bl(2, 0));
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+
+        // The piece needs to move and rotate on the ground to get into the hole.
+        assert!(rules.can_reach_strictly(goal, board, spawn));
+        assert!(rules
+            .with_move_reset_limit(15)
+            .can_reach_strictly(goal, board, spawn));
+        assert!(!rules
+            .with_move_reset_limit(0)
+            .can_reach_strictly(goal, board, spawn));
+
+        let limited = rules.with_move_reset_limit(2);
+        assert!(limited.can_reach_strictly(goal, board, spawn));
+        assert!(limited.generate_all_moves(board, spawn).contains(&goal));
+
+        let limited = rules.with_move_reset_limit(1);
+        assert!(!limited.can_reach(goal, board, spawn));
+        assert!(!limited.generate_all_moves(board, spawn).contains(&goal));
+    }
+
+    #[test]
+    fn generate_moves_batch() {
+        let board = Board64::from_str(
+            " \
+            ..........\
+            #.........\
+            ##.....###\
+            ##....####\
+            ##...#####\
+            ##....####\
+        ",
+        )
+        .unwrap();
+        let spawns = [
+            piece!(TN),
+            piece!(IN),
+            piece!(ON),
+            piece!(LN),
+            piece!(JN),
+            piece!(SN),
+            piece!(ZN),
+        ]
+        .map(|piece| piece.with(bl(4, 20)));
+
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let rules = MoveRules::srs(allow_move);
+
+            let sets = rules.generate_all_moves_batch(board, spawns);
+            for (set, spawn) in sets.into_iter().zip(spawns) {
+                assert_eq!(set, rules.generate_all_moves_set(board, spawn));
+            }
+
+            let sets = rules.generate_minimized_moves_batch(board, spawns);
+            for (set, spawn) in sets.into_iter().zip(spawns) {
+                assert_eq!(set, rules.generate_minimized_moves_set(board, spawn));
+            }
+        }
+    }
+
+    #[test]
+    fn can_reach_small_boards() {
+        let board = Board8::from_str(
+            "\
+            ..........\
+            ...#......\
+            #####_####\
+            ####__####\
+            ####_#####\
+            ",
+        )
+        .unwrap();
+
+        let srs_softdrop = MoveRules::srs(AllowMove::Softdrop);
+
+        // Spawn at the top of the board.
+        let spawn = piece!(ZN).with(cc(4, 6));
+        assert!(srs_softdrop.can_reach(piece!(ZE).with(bl(4, 0)), board, spawn.into()));
+        assert!(srs_softdrop.can_reach_strictly(piece!(ZE).with(bl(4, 0)), board, spawn.into()));
+        assert!(!srs_softdrop.can_reach_strictly(piece!(ZW).with(bl(4, 0)), board, spawn.into()));
+
+        // Rows above the ceiling are not available.
+        assert!(!srs_softdrop.can_reach(piece!(ZN).with(bl(0, 7)), board, spawn.into()));
+
+        // Spawn at the highest row.
+        let spawn = piece!(IN).with(cc(4, 7));
+        assert!(srs_softdrop.can_reach(piece!(IN).with(bl(4, 3)), board, spawn.into()));
+    }
+
+    #[test]
+    fn initial_rotation() {
+        let mut board = Board64::blank();
+        board.set_at(Location::new(3, 20));
+        let spawn = piece!(IN).with(bl(3, 20));
+
+        let normal = MoveRules::srs(AllowMove::Softdrop);
+        let irs = normal.with_spawn_policy(SpawnPolicy::InitialRotation);
+        assert!(normal.spawns(board, spawn).is_empty());
+        assert_eq!(
+            normal.try_generate_all_moves(board, spawn),
+            Err(MoveError::InvalidSpawn(spawn))
+        );
+
+        let spawns = irs.spawns(board, spawn);
+        assert_eq!(spawns.len(), 2);
+        assert!(spawns
+            .iter()
+            .all(|spawn| spawn.is_in_free_space(&board)
+                && spawn.piece.orientation != Orientation::North));
+        let moves = irs.try_generate_minimized_moves(board, spawn).unwrap();
+        assert!(moves.contains(&piece!(IN).with(bl(0, 0))));
+        assert!(irs.can_reach(piece!(IN).with(bl(0, 0)), board, spawn));
+        assert!(irs.can_reach_strictly(piece!(IS).with(bl(6, 0)), board, spawn));
+
+        // The normal spawn is also included when it's not blocked.
+        let board = Board64::blank();
+        for shape in Shape::all_iter() {
+            let spawn = shape.with(Orientation::North).with(bl(3, 20));
+            assert_eq!(
+                irs.generate_all_moves(board, spawn),
+                normal.generate_all_moves(board, spawn)
+            );
+        }
+    }
+
+    #[test]
+    fn generate_moves_with_dynamic_kicks() {
+        // SRS tested one by one with `test_kick_with()`.
+        struct DynamicSrs;
+
+        impl RotationSystem for DynamicSrs {
+            fn iter_kicks(&self, piece: Piece, rotation: Rotation) -> std::slice::Iter<'_, Kick> {
+                SrsKickTable.iter_kicks(piece, rotation)
+            }
+
+            fn is_moving_in_rotation(&self, shape: Shape) -> bool {
+                SrsKickTable.is_moving_in_rotation(shape)
+            }
+
+            fn has_static_kicks(&self) -> bool {
+                false
+            }
+        }
+
+        let board = Board64::from_str(
+            "\
+            XXX.......\
+            XX........\
+            XX.XXXXXXX\
+            XX..XXXXXX\
+            XX.XXXXXXX\
+            ",
+        )
+        .unwrap();
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let expected = MoveRules::srs(allow_move);
+            let rules = MoveRules::new(&DynamicSrs, allow_move);
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                assert_eq!(
+                    rules.generate_all_moves(board, spawn),
+                    expected.generate_all_moves(board, spawn),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generate_moves_with_piece_blocks() {
+        // SRS where the center of rotation is the bottom-left of the blocks.
+        struct BottomLeftSrs {
+            piece_blocks: Vec<PieceBlocks>,
+            kicks: Vec<Vec<Kick>>,
+        }
+
+        impl BottomLeftSrs {
+            fn new() -> Self {
+                let pieces = Piece::all_iter().collect::<Vec<_>>();
+                let piece_blocks = pieces
+                    .iter()
+                    .map(|&piece| {
+                        let default = piece.to_piece_blocks();
+                        let offsets = default.offsets.map(|offset| offset - default.bottom_left);
+                        PieceBlocks::with_offsets(piece, offsets)
+                    })
+                    .collect();
+                let kicks = pieces
+                    .iter()
+                    .flat_map(|&piece| {
+                        [Rotation::Cw, Rotation::Ccw].map(|rotation| {
+                            let shift = piece.rotate(rotation).to_piece_blocks().bottom_left
+                                - piece.to_piece_blocks().bottom_left;
+                            SrsKickTable
+                                .iter_kicks(piece, rotation)
+                                .map(|kick| Kick::new(kick.offset + shift))
+                                .collect()
+                        })
+                    })
+                    .collect();
+                Self {
+                    piece_blocks,
+                    kicks,
+                }
+            }
+
+            fn index(piece: Piece) -> usize {
+                piece.shape as usize * 4 + piece.orientation as usize
+            }
+        }
+
+        impl RotationSystem for BottomLeftSrs {
+            fn iter_kicks(&self, piece: Piece, rotation: Rotation) -> std::slice::Iter<'_, Kick> {
+                self.kicks[Self::index(piece) * 2 + rotation as usize].iter()
+            }
+
+            fn is_moving_in_rotation(&self, shape: Shape) -> bool {
+                SrsKickTable.is_moving_in_rotation(shape)
+            }
+
+            fn piece_blocks(&self, piece: Piece) -> &PieceBlocks {
+                &self.piece_blocks[Self::index(piece)]
+            }
+        }
+
+        let rotation_system = BottomLeftSrs::new();
+
+        // The center is the bottom-left.
+        let placement = piece!(TN).with(bl(3, 4));
+        assert_eq!(
+            placement.to_cc_placement_in(&rotation_system),
+            piece!(TN).with(cc(3, 4))
+        );
+        assert_eq!(
+            piece!(TN)
+                .with(cc(3, 4))
+                .to_bl_placement_in(&rotation_system),
+            placement
+        );
+        let kicked = rotation_system
+            .test_kick(
+                &Board64::blank(),
+                placement.to_cc_placement_in(&rotation_system),
+                Rotation::Cw,
+            )
+            .unwrap();
+        assert_eq!(
+            kicked.destination.to_bl_placement_in(&rotation_system),
+            piece!(TE).with(bl(4, 3))
+        );
+
+        let board = Board64::from_str(
+            "\
+            XXX.......\
+            XX........\
+            XX.XXXXXXX\
+            XX..XXXXXX\
+            XX.XXXXXXX\
+            ",
+        )
+        .unwrap();
+        for allow_move in [AllowMove::Softdrop, AllowMove::Harddrop, AllowMove::TwentyG] {
+            let expected = MoveRules::srs(allow_move);
+            let rules = MoveRules::new(&rotation_system, allow_move);
+            for shape in Shape::all_iter() {
+                let spawn = shape.with(Orientation::North).with(bl(4, 20));
+                assert_eq!(
+                    rules.generate_all_moves(board, spawn),
+                    expected.generate_all_moves(board, spawn),
+                );
+
+                let expected = expected.with_move_reset_limit(3);
+                let rules = MoveRules::new(&rotation_system, allow_move).with_move_reset_limit(3);
+                assert_eq!(
+                    rules.generate_all_moves(board, spawn),
+                    expected.generate_all_moves(board, spawn),
+                );
+            }
+        }
+    }
 }
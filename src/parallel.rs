@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Returns the count of threads to use. Zero means the available parallelism.
+#[inline]
+fn resolve_threads(threads: usize) -> usize {
+    if threads == 0 {
+        thread::available_parallelism().map_or(1, |it| it.get())
+    } else {
+        threads
+    }
+}
+
+/// Applies the function to each item on multiple threads, and returns the outputs in the order of the items.
+/// The results don't depend on the count of threads. Zero threads use the available parallelism.
+///
+/// The items are taken one by one, so the uneven costs of items are balanced.
+/// If the function panics, the panic is propagated after all threads finish.
+/// ```
+/// use bitris::parallel;
+/// let squares = parallel::map(&[1, 2, 3, 4, 5], 3, |&it| it * it);
+/// assert_eq!(squares, vec![1, 4, 9, 16, 25]);
+/// ```
+pub fn map<I, O, F>(items: &[I], threads: usize, f: F) -> Vec<O>
+where
+    I: Sync,
+    O: Send,
+    F: Fn(&I) -> O + Sync,
+{
+    let threads = resolve_threads(threads).min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let (f, next) = (&f, &next);
+    let mut outputs: Vec<Option<O>> = items.iter().map(|_| None).collect();
+    thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut outputs = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if items.len() <= index {
+                            break;
+                        }
+                        outputs.push((index, f(&items[index])));
+                    }
+                    outputs
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            match handle.join() {
+                Ok(results) => {
+                    for (index, output) in results {
+                        outputs[index] = Some(output);
+                    }
+                }
+                Err(payload) => std::panic::resume_unwind(payload),
+            }
+        }
+    });
+
+    outputs.into_iter().map(Option::unwrap).collect()
+}
+
+/// Returns the count of items that satisfy the predicate, checked on multiple threads.
+/// Zero threads use the available parallelism.
+///
+/// It's useful to calculate the success rate over the sequences, such as the perfect clear percent.
+/// ```
+/// use itertools::Itertools;
+/// use bitris::parallel;
+/// use bitris::prelude::*;
+///
+/// // The sequences that start with T or I.
+/// let sequences = Shape::all_iter().permutations(3).collect::<Vec<_>>();
+/// let successes = parallel::count(&sequences, 0, |sequence| matches!(sequence[0], Shape::T | Shape::I));
+/// assert_eq!(successes * 7, sequences.len() * 2);
+/// ```
+pub fn count<I, F>(items: &[I], threads: usize, predicate: F) -> usize
+where
+    I: Sync,
+    F: Fn(&I) -> bool + Sync,
+{
+    map(items, threads, predicate)
+        .into_iter()
+        .filter(|&it| it)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parallel;
+
+    #[test]
+    fn independent_of_threads() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let expected = items.iter().map(|it| it * 3 + 1).collect::<Vec<_>>();
+        for threads in [0, 1, 2, 7, 2000] {
+            assert_eq!(parallel::map(&items, threads, |it| it * 3 + 1), expected);
+            assert_eq!(parallel::count(&items, threads, |it| it % 3 == 0), 334);
+        }
+        assert_eq!(parallel::map(&[] as &[u64], 4, |it| *it), vec![]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn propagate_panic() {
+        parallel::map(&[1, 2, 3, 4], 2, |&it| {
+            assert_ne!(it, 3);
+            it
+        });
+    }
+}
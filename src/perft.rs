@@ -0,0 +1,348 @@
+use fxhash::FxHashMap;
+
+use crate::boards::Board64;
+use crate::pieces::Shape;
+use crate::{
+    CancelToken, CancelWatch, GameRules, MoveRules, OrderCursor, PopOp, RotationSystem,
+    SearchStatus,
+};
+
+/// Enumerates the distinct boards after placing the pieces, like perft in chess.
+/// Lines are cleared after each placement, and the boards are deduplicated after each piece.
+///
+/// The pieces spawn by the game rules, and the branches that top out are dropped.
+/// It's useful to make opening books and to benchmark the move generation.
+/// ```
+/// use bitris::prelude::*;
+/// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+/// let perft = Perft::new(&move_rules, GameRules::guideline());
+///
+/// let board = Board64::blank();
+/// assert_eq!(perft.count_boards(board, None, &[Shape::O], 1), 9);
+/// assert_eq!(perft.count_boards(board, None, &[Shape::O, Shape::I], 1), 9 + 17);
+/// assert_eq!(perft.without_hold().count_boards(board, None, &[Shape::O, Shape::I], 1), 9);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Perft<'a, T>
+where
+    T: RotationSystem,
+{
+    pub move_rules: &'a MoveRules<'a, T>,
+    pub game_rules: GameRules,
+    pub allows_hold: bool,
+}
+
+impl<'a, T> Perft<'a, T>
+where
+    T: RotationSystem,
+{
+    /// Returns the search that allows hold.
+    #[inline]
+    pub fn new(move_rules: &'a MoveRules<'a, T>, game_rules: GameRules) -> Self {
+        Self {
+            move_rules,
+            game_rules,
+            allows_hold: true,
+        }
+    }
+
+    /// Returns the search that doesn't allow hold. The held piece is never used.
+    #[inline]
+    pub fn without_hold(self) -> Self {
+        Self {
+            allows_hold: false,
+            ..self
+        }
+    }
+
+    /// Returns the distinct boards after placing `depth` pieces, sorted.
+    /// The pieces are taken from the hold and the queue. With hold, any piece can be held at each step.
+    ///
+    /// Panics if the pieces are fewer than the depth.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::prelude::*;
+    /// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let perft = Perft::new(&move_rules, GameRules::guideline());
+    ///
+    /// let board = Board64::from_str("XXXXXX....").unwrap();
+    /// let boards = perft.enumerate_boards(board, Some(Shape::I), &[Shape::O], 1);
+    /// assert!(boards.contains(&Board64::blank()));
+    /// ```
+    pub fn enumerate_boards(
+        &self,
+        board: Board64,
+        hold: Option<Shape>,
+        queue: &[Shape],
+        depth: usize,
+    ) -> Vec<Board64> {
+        self.search(board, hold, queue, depth, None)
+    }
+
+    /// Returns the count of `enumerate_boards()`.
+    ///
+    /// Panics if the pieces are fewer than the depth.
+    pub fn count_boards(
+        &self,
+        board: Board64,
+        hold: Option<Shape>,
+        queue: &[Shape],
+        depth: usize,
+    ) -> usize {
+        self.search(board, hold, queue, depth, None).len()
+    }
+
+    /// It's similar to `enumerate_boards()` except that the search stops when the token is cancelled.
+    /// The boards are enumerated depth by depth, so if it stops, the boards of the deepest completed depth are returned.
+    ///
+    /// Panics if the pieces are fewer than the depth.
+    /// ```
+    /// use std::time::Duration;
+    /// use bitris::prelude::*;
+    /// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let perft = Perft::new(&move_rules, GameRules::guideline());
+    /// let board = Board64::blank();
+    ///
+    /// let token = CancelToken::with_timeout(Duration::from_secs(60));
+    /// let (boards, status) = perft.enumerate_boards_with_cancel(board, None, &[Shape::O], 1, &token);
+    /// assert_eq!((boards.len(), status), (9, SearchStatus::Completed));
+    ///
+    /// // It stops before placing the first piece, so the board at the depth zero is returned.
+    /// let token = CancelToken::with_timeout(Duration::ZERO);
+    /// let (boards, status) = perft.enumerate_boards_with_cancel(board, None, &[Shape::O], 1, &token);
+    /// assert_eq!((boards, status), (vec![board], SearchStatus::TimedOut));
+    /// ```
+    pub fn enumerate_boards_with_cancel(
+        &self,
+        board: Board64,
+        hold: Option<Shape>,
+        queue: &[Shape],
+        depth: usize,
+        cancel: &CancelToken,
+    ) -> (Vec<Board64>, SearchStatus) {
+        let watch = CancelWatch::new(cancel);
+        let boards = self.search(board, hold, queue, depth, Some(&watch));
+        (boards, watch.status())
+    }
+
+    /// Returns the count of `enumerate_boards_with_cancel()`, and the status.
+    ///
+    /// Panics if the pieces are fewer than the depth.
+    pub fn count_boards_with_cancel(
+        &self,
+        board: Board64,
+        hold: Option<Shape>,
+        queue: &[Shape],
+        depth: usize,
+        cancel: &CancelToken,
+    ) -> (usize, SearchStatus) {
+        let (boards, status) = self.enumerate_boards_with_cancel(board, hold, queue, depth, cancel);
+        (boards.len(), status)
+    }
+
+    fn search(
+        &self,
+        board: Board64,
+        hold: Option<Shape>,
+        queue: &[Shape],
+        depth: usize,
+        watch: Option<&CancelWatch>,
+    ) -> Vec<Board64> {
+        let shapes = match (hold, self.allows_hold) {
+            (Some(hold), true) => [hold].into_iter().chain(queue.iter().copied()).collect(),
+            _ => queue.to_vec(),
+        };
+        assert!(
+            depth <= shapes.len(),
+            "The pieces are fewer than the depth: pieces={}, depth={}",
+            shapes.len(),
+            depth,
+        );
+
+        let ops: &[PopOp] = if self.allows_hold {
+            &[PopOp::First, PopOp::Second]
+        } else {
+            &[PopOp::First]
+        };
+
+        // The states are deduplicated by the board and the piece that can be used next (the hold or the current).
+        let cursor = OrderCursor::from(&shapes);
+        let mut states = FxHashMap::<(Board64, Option<Shape>), OrderCursor<Shape>>::default();
+        states.insert((board, cursor.peek_first().copied()), cursor);
+
+        'depth: for _ in 0..depth {
+            let mut next_states = FxHashMap::default();
+            for (&(board, _), cursor) in &states {
+                if watch.is_some_and(|watch| watch.should_stop()) {
+                    // This depth is not completed, so the boards of the previous depth are the results.
+                    break 'depth;
+                }
+
+                for &op in ops {
+                    let (shape, next_cursor) = cursor.pop(op);
+                    let Some(&shape) = shape else {
+                        continue;
+                    };
+
+                    let spawn = self.game_rules.spawn(shape);
+                    if self.move_rules.spawns(board, spawn).is_empty() {
+                        continue;
+                    }
+
+                    let moves = self.move_rules.generate_minimized_moves(board, spawn);
+                    for placement in moves {
+                        if self.game_rules.is_lock_out(placement) {
+                            continue;
+                        }
+
+                        let mut next_board = board;
+                        placement.place_on_and_clear_lines(&mut next_board);
+                        next_states
+                            .entry((next_board, next_cursor.peek_first().copied()))
+                            .or_insert(next_cursor);
+                    }
+                }
+            }
+            states = next_states;
+        }
+
+        let mut boards = states
+            .into_keys()
+            .map(|(board, _)| board)
+            .collect::<Vec<_>>();
+        boards.sort_unstable();
+        boards.dedup();
+        boards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn depth_zero() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        let board = Board64::from_str("XXXXX.....").unwrap();
+        assert_eq!(perft.enumerate_boards(board, None, &[], 0), vec![board]);
+    }
+
+    #[test]
+    fn one_piece() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        let board = Board64::blank();
+        for shape in Shape::all_iter() {
+            let spawn = GameRules::guideline().spawn(shape);
+            assert_eq!(
+                perft.count_boards(board, None, &[shape], 1),
+                move_rules.generate_minimized_moves(board, spawn).len(),
+            );
+        }
+    }
+
+    #[test]
+    fn line_clears_merge_boards() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+
+        // O clears the lines at the right.
+        let board = Board64::from_str(
+            "\
+            XXXXXXXX..\
+            XXXXXXXX..\
+            ",
+        )
+        .unwrap();
+        let boards = perft.enumerate_boards(board, None, &[Shape::O], 1);
+        assert_eq!(boards.len(), 9);
+        assert!(boards.contains(&Board64::blank()));
+
+        // Two O's fill the 2x4 hole in either order, and the boards are deduplicated.
+        let board = Board64::from_str(
+            "\
+            XXXXXX....\
+            XXXXXX....\
+            ",
+        )
+        .unwrap();
+        let boards = perft.enumerate_boards(board, None, &[Shape::O, Shape::O], 2);
+        assert!(boards.contains(&Board64::blank()));
+        assert!(boards.windows(2).all(|it| it[0] < it[1]));
+    }
+
+    #[test]
+    fn hold() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        let board = Board64::blank();
+
+        // [T] I O with hold: T or I is placed first, and then any of the rest.
+        let with_hold = perft.count_boards(board, Some(Shape::T), &[Shape::I, Shape::O], 2);
+        let without_hold =
+            perft
+                .without_hold()
+                .count_boards(board, Some(Shape::T), &[Shape::I, Shape::O], 2);
+        assert!(without_hold < with_hold);
+
+        let orders = [
+            [Shape::T, Shape::I],
+            [Shape::I, Shape::T],
+            [Shape::I, Shape::O],
+            [Shape::T, Shape::O],
+        ];
+        let mut expected = orders
+            .iter()
+            .flat_map(|order| perft.without_hold().enumerate_boards(board, None, order, 2))
+            .collect::<Vec<_>>();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(with_hold, expected.len());
+    }
+
+    #[test]
+    fn top_out() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        let board = Board64::filled_up_to(21);
+        assert_eq!(perft.count_boards(board, None, &[Shape::T], 1), 0);
+    }
+
+    #[test]
+    fn cancel() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        let board = Board64::blank();
+        let queue = [Shape::T, Shape::I, Shape::O];
+
+        let (count, status) =
+            perft.count_boards_with_cancel(board, None, &queue, 2, &CancelToken::new());
+        assert_eq!(count, perft.count_boards(board, None, &queue, 2));
+        assert_eq!(status, SearchStatus::Completed);
+
+        // It stops before the last depth, and the boards of the depth zero are returned.
+        let token = CancelToken::new();
+        token.cancel();
+        assert_eq!(
+            perft.enumerate_boards_with_cancel(board, None, &queue, 2, &token),
+            (vec![board], SearchStatus::Cancelled)
+        );
+
+        // Nothing to search at the depth zero.
+        assert_eq!(
+            perft.enumerate_boards_with_cancel(board, None, &queue, 0, &token),
+            (vec![board], SearchStatus::Completed)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_deep() {
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let perft = Perft::new(&move_rules, GameRules::guideline());
+        perft.count_boards(Board64::blank(), Some(Shape::T), &[Shape::I], 3);
+    }
+}
@@ -2,8 +2,10 @@ pub use orientation::*;
 pub use piece::*;
 pub use piece_blocks::*;
 pub use shape::*;
+pub use shape_counter::*;
 
 mod orientation;
 mod piece;
 mod piece_blocks;
 mod shape;
+mod shape_counter;
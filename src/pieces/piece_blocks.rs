@@ -63,6 +63,19 @@ impl PieceBlocks {
             }
         }
 
+        Self::with_offsets(piece, offsets(piece))
+    }
+
+    /// Returns the blocks with the offsets from the center of the piece.
+    /// Use it to define the blocks of a rotation system. See `PieceBlocksTable`.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let blocks = PieceBlocks::with_offsets(piece!(TN), [dd(0, 0), dd(1, 0), dd(2, 0), dd(1, 1)]);
+    /// assert_eq!(blocks.bottom_left, dd(0, 0));
+    /// assert!(blocks.has_same_form_as(piece!(TN).to_piece_blocks()));
+    /// ```
+    pub const fn with_offsets(piece: Piece, offsets: [Offset; 4]) -> Self {
         const fn min_max_dx(offsets: &[Offset; 4]) -> (i32, i32) {
             let mut index = 0;
             let mut min_dx = i32::MAX;
@@ -95,7 +108,6 @@ impl PieceBlocks {
             (min_dy, max_dy)
         }
 
-        let offsets = offsets(piece);
         let (min_dx, max_dx) = min_max_dx(&offsets);
         let (min_dy, max_dy) = min_max_dy(&offsets);
         PieceBlocks {
@@ -158,6 +170,22 @@ impl PieceBlocks {
         }
         vec
     }
+
+    /// Returns true if the blocks have the same form, regardless of the center.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// assert!(piece!(SN).to_piece_blocks().has_same_form_as(piece!(SS).to_piece_blocks()));
+    /// assert!(!piece!(TN).to_piece_blocks().has_same_form_as(piece!(TS).to_piece_blocks()));
+    /// ```
+    pub fn has_same_form_as(&self, other: &PieceBlocks) -> bool {
+        let normalize = |blocks: &PieceBlocks| {
+            let mut offsets = blocks.offsets.map(|offset| offset - blocks.bottom_left);
+            offsets.sort();
+            offsets
+        };
+        normalize(self) == normalize(other)
+    }
 }
 
 add_member_for_from!(Piece, piece, to PieceBlocks);
@@ -309,6 +337,22 @@ impl PieceBlocksFactory {
     }
 }
 
+/// Provides the blocks of each piece.
+///
+/// The forms of the blocks must be the same as `PieceBlocksFactory`, but the positions relative to the center of rotation can differ.
+/// So placements based on the center (`CcPlacement`) depend on the table, but placements based on the blocks (`BlPlacement` and `TrPlacement`) don't.
+/// Rotation systems provide it by `RotationSystem::piece_blocks()`.
+pub trait PieceBlocksTable {
+    fn get(&self, piece: Piece) -> &PieceBlocks;
+}
+
+impl PieceBlocksTable for PieceBlocksFactory {
+    #[inline]
+    fn get(&self, piece: Piece) -> &PieceBlocks {
+        PieceBlocksFactory::get(self, piece)
+    }
+}
+
 impl From<Piece> for PieceBlocks {
     fn from(piece: Piece) -> Self {
         Self::new(piece)
@@ -345,6 +389,11 @@ mod tests {
         Board64::blank()
     }
 
+    #[fixture]
+    pub fn board128() -> Board128 {
+        Board128::blank()
+    }
+
     #[test]
     fn piece_blocks_works() {
         assert_eq!(size_of::<PieceBlocks>(), 60);
@@ -367,6 +416,7 @@ mod tests {
     #[case::board16(board16())]
     #[case::board32(board32())]
     #[case::board64(board64())]
+    #[case::board128(board128())]
     fn all_boards(#[case] board: Board<T>) {}
 
     #[apply(all_boards)]
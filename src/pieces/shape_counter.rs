@@ -0,0 +1,336 @@
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use crate::pieces::Shape;
+use crate::OrderCursor;
+
+/// A multiset of shapes, counting the pieces regardless of the order.
+/// Each count takes 8 bits packed into a `u64`, so it's cheap to copy and to use as a hash key.
+///
+/// Each count is up to 255. Exceeding it or going below zero panics.
+/// ```
+/// use bitris::prelude::*;
+/// let counter = ShapeCounter::from_iter([Shape::T, Shape::I, Shape::T]);
+/// assert_eq!(counter.count(Shape::T), 2);
+/// assert_eq!(counter.count(Shape::O), 0);
+/// assert_eq!(counter.len(), 3);
+///
+/// let counter = counter + Shape::O - Shape::T;
+/// assert_eq!(counter, ShapeCounter::from_iter([Shape::O, Shape::I, Shape::T]));
+/// assert!(counter.contains_all(&ShapeCounter::one(Shape::I)));
+/// assert_eq!(counter.iter().collect::<Vec<_>>(), vec![Shape::T, Shape::I, Shape::O]);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ShapeCounter {
+    key: u64,
+}
+
+impl ShapeCounter {
+    const BITS: u32 = 8;
+    const MASK: u64 = (1 << Self::BITS) - 1;
+
+    #[inline]
+    const fn shift(shape: Shape) -> u32 {
+        shape as u32 * Self::BITS
+    }
+
+    /// Returns the counter without shapes.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self { key: 0 }
+    }
+
+    /// Returns the counter with one shape.
+    #[inline]
+    pub const fn one(shape: Shape) -> Self {
+        Self {
+            key: 1 << Self::shift(shape),
+        }
+    }
+
+    /// Returns the count of the shape.
+    #[inline]
+    pub const fn count(&self, shape: Shape) -> u32 {
+        ((self.key >> Self::shift(shape)) & Self::MASK) as u32
+    }
+
+    /// Returns the count of all shapes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        Shape::all_iter()
+            .map(|shape| self.count(shape) as usize)
+            .sum()
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.key == 0
+    }
+
+    /// Returns true if all the shapes of the other are contained, including the counts.
+    /// ```
+    /// use bitris::prelude::*;
+    /// let counter = ShapeCounter::from_iter([Shape::T, Shape::T, Shape::I]);
+    /// assert!(counter.contains_all(&ShapeCounter::from_iter([Shape::T, Shape::T])));
+    /// assert!(!counter.contains_all(&ShapeCounter::from_iter([Shape::T, Shape::T, Shape::T])));
+    /// assert!(counter.contains_all(&ShapeCounter::empty()));
+    /// ```
+    #[inline]
+    pub fn contains_all(&self, other: &Self) -> bool {
+        Shape::all_iter().all(|shape| other.count(shape) <= self.count(shape))
+    }
+
+    /// Returns the difference, or None if the other is not contained.
+    #[inline]
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        if self.contains_all(&other) {
+            // No borrows across the counts since each count is greater than or equal to the other.
+            Some(Self {
+                key: self.key - other.key,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sum, or None if any count exceeds 255.
+    #[inline]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let mut key = 0;
+        for shape in Shape::all_iter() {
+            let count = self.count(shape) as u64 + other.count(shape) as u64;
+            if Self::MASK < count {
+                return None;
+            }
+            key |= count << Self::shift(shape);
+        }
+        Some(Self { key })
+    }
+
+    /// Returns an iterator of the shapes in the order of `Shape`, repeating each shape by its count.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Shape> + '_ {
+        self.iter_counts()
+            .flat_map(|(shape, count)| std::iter::repeat_n(shape, count as usize))
+    }
+
+    /// Returns an iterator of the shapes and their counts in the order of `Shape`. The shapes not contained are skipped.
+    #[inline]
+    pub fn iter_counts(&self) -> impl Iterator<Item = (Shape, u32)> + '_ {
+        Shape::all_iter()
+            .map(|shape| (shape, self.count(shape)))
+            .filter(|&(_, count)| 0 < count)
+    }
+}
+
+impl Add for ShapeCounter {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("The count of a shape exceeds 255.")
+    }
+}
+
+impl Add<Shape> for ShapeCounter {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Shape) -> Self::Output {
+        self + Self::one(rhs)
+    }
+}
+
+impl AddAssign for ShapeCounter {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl AddAssign<Shape> for ShapeCounter {
+    #[inline]
+    fn add_assign(&mut self, rhs: Shape) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for ShapeCounter {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("The shapes to subtract are not contained.")
+    }
+}
+
+impl Sub<Shape> for ShapeCounter {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Shape) -> Self::Output {
+        self - Self::one(rhs)
+    }
+}
+
+impl SubAssign for ShapeCounter {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl SubAssign<Shape> for ShapeCounter {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Shape) {
+        *self = *self - rhs;
+    }
+}
+
+impl Extend<Shape> for ShapeCounter {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Shape>>(&mut self, iter: I) {
+        for shape in iter {
+            *self += shape;
+        }
+    }
+}
+
+impl<'a> Extend<&'a Shape> for ShapeCounter {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a Shape>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl FromIterator<Shape> for ShapeCounter {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Shape>>(iter: I) -> Self {
+        let mut counter = Self::empty();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<'a> FromIterator<&'a Shape> for ShapeCounter {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = &'a Shape>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl From<&[Shape]> for ShapeCounter {
+    #[inline]
+    fn from(shapes: &[Shape]) -> Self {
+        shapes.iter().collect()
+    }
+}
+
+impl From<&OrderCursor<'_, Shape>> for ShapeCounter {
+    /// Returns the counter of the shapes not used yet.
+    /// ```
+    /// use bitris::prelude::*;
+    /// let order = vec![Shape::T, Shape::I, Shape::T];
+    /// let (_, cursor) = OrderCursor::from(&order).pop(PopOp::First);
+    /// assert_eq!(ShapeCounter::from(&cursor), ShapeCounter::from_iter([Shape::I, Shape::T]));
+    /// ```
+    #[inline]
+    fn from(cursor: &OrderCursor<'_, Shape>) -> Self {
+        cursor.iter_remaining().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fxhash::FxHashSet;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn counts() {
+        let mut counter = ShapeCounter::empty();
+        assert!(counter.is_empty());
+        assert_eq!(counter.len(), 0);
+
+        for shape in Shape::all_iter() {
+            for _ in 0..=shape as u32 {
+                counter += shape;
+            }
+        }
+        assert_eq!(counter.len(), 28);
+        for shape in Shape::all_iter() {
+            assert_eq!(counter.count(shape), shape as u32 + 1);
+        }
+
+        counter -= ShapeCounter::from_iter([Shape::Z, Shape::Z, Shape::T]);
+        assert_eq!(counter.count(Shape::T), 0);
+        assert_eq!(counter.count(Shape::Z), 5);
+        assert_eq!(
+            counter.iter_counts().collect::<Vec<_>>(),
+            vec![
+                (Shape::I, 2),
+                (Shape::O, 3),
+                (Shape::L, 4),
+                (Shape::J, 5),
+                (Shape::S, 6),
+                (Shape::Z, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn boundaries() {
+        let full = ShapeCounter::from_iter([Shape::Z; 255]);
+        assert_eq!(full.count(Shape::Z), 255);
+        assert_eq!(full.checked_add(ShapeCounter::one(Shape::Z)), None);
+        assert_eq!(
+            full.checked_add(ShapeCounter::one(Shape::S))
+                .map(|it| it.count(Shape::S)),
+            Some(1)
+        );
+
+        let counter = ShapeCounter::from_iter([Shape::T, Shape::I]);
+        assert_eq!(counter.checked_sub(ShapeCounter::one(Shape::O)), None);
+        assert_eq!(
+            counter.checked_sub(ShapeCounter::one(Shape::I)),
+            Some(ShapeCounter::one(Shape::T))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_not_contained() {
+        let _ = ShapeCounter::one(Shape::T) - Shape::I;
+    }
+
+    #[test]
+    fn order_free() {
+        use Shape::*;
+        let orders = [[T, I, O], [O, T, I], [I, O, T]];
+        let keys = orders
+            .iter()
+            .map(|order| ShapeCounter::from(&order[..]))
+            .collect::<FxHashSet<_>>();
+        assert_eq!(keys.len(), 1);
+
+        let counter = ShapeCounter::from_iter(&orders[0]);
+        assert_eq!(counter.iter().collect::<Vec<_>>(), vec![T, I, O]);
+        assert!(counter.contains_all(&ShapeCounter::from_iter([O, T])));
+        assert!(!ShapeCounter::from_iter([O, T]).contains_all(&counter));
+    }
+
+    #[test]
+    fn from_cursor() {
+        use Shape::*;
+        let order = vec![T, I, O, T];
+        let cursor = OrderCursor::from(&order);
+        assert_eq!(ShapeCounter::from(&cursor), ShapeCounter::from(&order[..]));
+
+        let (_, cursor) = cursor.pop(PopOp::Second);
+        assert_eq!(
+            ShapeCounter::from(&cursor),
+            ShapeCounter::from_iter([T, O, T])
+        );
+    }
+}
@@ -5,7 +5,9 @@ use tinyvec::ArrayVec;
 use crate::boards::{BoardOp, Lines};
 use crate::coordinates::{BlPosition, Location, Offset};
 use crate::internal_macros::{add_member_for_from, forward_ref_from, forward_ref_op};
-use crate::pieces::{Orientation, Piece, PieceBlocks, PieceBlocksFactory, Shape};
+use crate::pieces::{
+    Orientation, Piece, PieceBlocks, PieceBlocksFactory, PieceBlocksTable, Shape, ToCcPosition,
+};
 use crate::placements::{CcPlacement, PlacedPiece, TrPlacement};
 use crate::{Rotate, Rotation};
 
@@ -27,6 +29,20 @@ impl BlPlacement {
         PieceBlocksFactory.get(self.piece)
     }
 
+    /// Returns the placement based on the center defined by the table.
+    /// It's the same as `to_cc_placement()` with the default table.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let placement = piece!(TN).with(bl(1, 2));
+    /// assert_eq!(placement.to_cc_placement_in(&PieceBlocksFactory), placement.to_cc_placement());
+    /// ```
+    #[inline]
+    pub fn to_cc_placement_in(self, table: &impl PieceBlocksTable) -> CcPlacement {
+        let position = self.position.to_cc_position(table.get(self.piece));
+        CcPlacement::new(self.piece, position)
+    }
+
     #[inline]
     pub const fn shape(&self) -> Shape {
         self.piece.shape
@@ -155,7 +171,7 @@ impl BlPlacement {
     /// Returns the lines cleared if succeed.
     /// Returns None if failed to place.
     #[inline]
-    pub fn place_on_and_clear_lines(&self, board: &mut impl BoardOp) -> Option<Lines> {
+    pub fn place_on_and_clear_lines<B: BoardOp>(&self, board: &mut B) -> Option<B::Lines> {
         self.to_cc_placement().place_on_and_clear_lines(board)
     }
 
@@ -178,6 +194,13 @@ impl BlPlacement {
     pub fn is_in_free_space(&self, board: &impl BoardOp) -> bool {
         self.to_cc_placement().is_in_free_space(board)
     }
+
+    /// Returns true if the placement cannot shift left, right, or up.
+    /// Whether the placement is in free space or not is independent of the result.
+    #[inline]
+    pub fn is_immobile(&self, board: &impl BoardOp) -> bool {
+        self.to_cc_placement().is_immobile(board)
+    }
 }
 
 impl fmt::Display for BlPlacement {
@@ -345,12 +368,18 @@ mod tests {
         Board64::blank()
     }
 
+    #[fixture]
+    pub fn board128() -> Board128 {
+        Board128::blank()
+    }
+
     #[template]
     #[rstest]
     #[case::board8(board8())]
     #[case::board16(board16())]
     #[case::board32(board32())]
     #[case::board64(board64())]
+    #[case::board128(board128())]
     fn all_boards(#[case] mut board: impl BoardOp) {}
 
     #[apply(all_boards)]
@@ -377,7 +406,7 @@ mod tests {
 
         assert_eq!(
             os.with(bl(8, 0)).place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0b11))
+            Some(Lines::new(0b11).into())
         );
         assert_eq!(board.count_blocks(), 0);
     }
@@ -5,7 +5,10 @@ use tinyvec::ArrayVec;
 use crate::boards::{BoardOp, Lines};
 use crate::coordinates::{dd, CcPosition, Location, Offset};
 use crate::internal_macros::{add_member_for_from, forward_ref_from, forward_ref_op};
-use crate::pieces::{Orientation, Piece, PieceBlocks, PieceBlocksFactory, Shape};
+use crate::pieces::{
+    Orientation, Piece, PieceBlocks, PieceBlocksFactory, PieceBlocksTable, Shape, ToBlPosition,
+    ToTrPosition,
+};
 use crate::placements::{BlPlacement, PlacedPiece, TrPlacement};
 use crate::{Rotate, Rotation};
 
@@ -27,6 +30,35 @@ impl CcPlacement {
         PieceBlocksFactory.get(self.piece)
     }
 
+    /// Returns the placement based on the bottom-left, regarding the center as defined by the table.
+    /// It's the same as `to_bl_placement()` with the default table.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let placement = piece!(TN).with(cc(2, 2));
+    /// assert_eq!(placement.to_bl_placement_in(&PieceBlocksFactory), piece!(TN).with(bl(1, 2)));
+    /// assert_eq!(placement.to_bl_placement_in(&SrsKickTable), placement.to_bl_placement());
+    /// ```
+    #[inline]
+    pub fn to_bl_placement_in(self, table: &impl PieceBlocksTable) -> BlPlacement {
+        let position = self.position.to_bl_position(table.get(self.piece));
+        BlPlacement::new(self.piece, position)
+    }
+
+    /// Returns the placement based on the top-right, regarding the center as defined by the table.
+    /// It's the same as `to_tr_placement()` with the default table.
+    #[inline]
+    pub fn to_tr_placement_in(self, table: &impl PieceBlocksTable) -> TrPlacement {
+        let position = self.position.to_tr_position(table.get(self.piece));
+        TrPlacement::new(self.piece, position)
+    }
+
+    /// Returns locations for each block, regarding the center as defined by the table.
+    #[inline]
+    pub fn locations_in(&self, table: &impl PieceBlocksTable) -> [Location; 4] {
+        table.get(self.piece).to_locations(self.position)
+    }
+
     #[inline]
     pub const fn shape(&self) -> Shape {
         self.piece.shape
@@ -168,7 +200,7 @@ impl CcPlacement {
     /// Returns the lines cleared if succeed.
     /// Returns None if failed to place.
     #[inline]
-    pub fn place_on_and_clear_lines(&self, board: &mut impl BoardOp) -> Option<Lines> {
+    pub fn place_on_and_clear_lines<B: BoardOp>(&self, board: &mut B) -> Option<B::Lines> {
         let placed = self.place_on(board);
         if !placed {
             return None;
@@ -204,6 +236,30 @@ impl CcPlacement {
             .into_iter()
             .all(|location| board.test_access(location) && board.is_free_at(location))
     }
+
+    /// Returns true if the placement cannot shift left, right, or up.
+    /// It's the condition of all-spins. See also `AllSpinRule::classify()`.
+    /// Whether the placement is in free space or not is independent of the result.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let board = Board64::from_str(
+    ///     "\
+    ///     ...X......\
+    ///     XX........\
+    ///     XXX..XXXXX\
+    ///     ",
+    /// ).unwrap();
+    /// assert!(piece!(ZN).with(cc(3, 0)).is_immobile(&board));
+    /// assert!(!piece!(ZN).with(cc(3, 0)).is_immobile(&Board64::blank()));
+    /// ```
+    #[inline]
+    pub fn is_immobile(&self, board: &impl BoardOp) -> bool {
+        [dd(-1, 0), dd(1, 0), dd(0, 1)]
+            .into_iter()
+            .all(|offset| !(*self + offset).is_in_free_space(board))
+    }
 }
 
 impl fmt::Display for CcPlacement {
@@ -453,31 +509,31 @@ mod tests {
             piece!(ON)
                 .with(cc(0, 0))
                 .place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0))
+            Some(Lines::new(0).into())
         );
         assert_eq!(
             piece!(ON)
                 .with(cc(2, 0))
                 .place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0))
+            Some(Lines::new(0).into())
         );
         assert_eq!(
             piece!(ON)
                 .with(cc(4, 0))
                 .place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0))
+            Some(Lines::new(0).into())
         );
         assert_eq!(
             piece!(ON)
                 .with(cc(6, 0))
                 .place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0))
+            Some(Lines::new(0).into())
         );
         assert_eq!(
             piece!(ON)
                 .with(cc(8, 0))
                 .place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0b11))
+            Some(Lines::new(0b11).into())
         );
         assert_eq!(board.count_blocks(), 0);
     }
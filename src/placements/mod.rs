@@ -4,6 +4,9 @@ pub use placed_piece::*;
 pub use placed_piece_blocks::*;
 pub use placed_piece_blocks_flow::*;
 pub use placement_flow::*;
+pub use placement_set::*;
+pub use tiling::*;
+pub use tiling_cache::*;
 pub use tr_placement::*;
 
 mod bl_placement;
@@ -12,4 +15,8 @@ mod placed_piece;
 mod placed_piece_blocks;
 mod placed_piece_blocks_flow;
 mod placement_flow;
+mod placement_set;
+mod refs_mask;
+mod tiling;
+mod tiling_cache;
 mod tr_placement;
@@ -1,4 +1,4 @@
-use crate::boards::{BoardOp, Lines};
+use crate::boards::{BoardOp, Lines, Lines128};
 use crate::coordinates::{bl, Location};
 use crate::internal_macros::forward_ref_from;
 use crate::placements::{BlPlacement, PlacedPiece};
@@ -11,7 +11,9 @@ pub(crate) fn place_according_to<T: BoardOp + Clone>(
     using_rows: Lines,
     intercepted_rows: Lines,
 ) -> Option<BlPlacement> {
-    let lines_cleared = board.clear_lines();
+    // The piece is within the lower 64 rows, so the rows above are not needed.
+    let lines_cleared: Lines128 = board.clear_lines().into();
+    let lines_cleared = lines_cleared.lower();
 
     // Whether cleared rows and the piece overlap or not.
     if using_rows.overlaps(&lines_cleared) {
@@ -1,14 +1,23 @@
+use std::rc::Rc;
+
 use fxhash::FxHashSet;
+use itertools::Either;
 
-use crate::boards::{Board64, BoardOp};
+use crate::boards::{Board64, BoardOp, PcPruning};
 use crate::coordinates::BlPosition;
 use crate::pieces::{Piece, Shape};
+use crate::placements::refs_mask::{RefsBitSet, RefsMask};
 use crate::placements::PlacedPieceBlocks;
 use crate::prelude::BlPlacement;
 use crate::{
-    GenerateInstruction, MoveRules, OrderCursor, PopOp, RotationSystem, SearchResult, With,
+    CancelToken, CancelWatch, GenerateInstruction, MoveRules, OrderCursor, PopOp, RotationSystem,
+    SearchResult, SearchStatus, With,
 };
 
+// The count of the first placements expanded before the orders are split into the tasks.
+#[cfg(feature = "parallel")]
+const PAR_SPLIT_DEPTH: usize = 3;
+
 /// Returns a flow finds that all placements have been successful from the initial board.
 ///
 /// `validator` receives (board before clearing, subsequent placement) and returns whether to continue searching the board.
@@ -25,35 +34,28 @@ fn find_one_combination_minimized_dyn<'a>(
         return Some(PlacedPieceBlocksFlow::new(initial_board, refs.clone()));
     }
 
-    assert!(refs.len() < 64, "refs length supports up to 64.");
-
-    struct Builder<'a, 'b> {
+    struct Builder<'a, 'b, M: RefsMask> {
         refs: &'a Vec<&'b PlacedPieceBlocks>,
         results: Vec<&'b PlacedPieceBlocks>,
-        visited: FxHashSet<u64>,
+        visited: FxHashSet<M>,
     }
 
-    impl Builder<'_, '_> {
+    impl<M: RefsMask> Builder<'_, '_, M> {
         fn build(
             &mut self,
             board: Board64,
-            remaining: u64,
+            remaining: M,
             validator: &impl Fn(&Board64, BlPlacement) -> SearchResult,
         ) -> bool {
-            let mut candidates = remaining;
-            while 0 < candidates {
-                let next_candidates = candidates & (candidates - 1);
-                let bit = candidates - next_candidates;
-                let next_remaining = remaining - bit;
-
-                candidates = next_candidates;
+            for index in remaining.indices() {
+                let next_remaining = remaining.without(index);
 
                 if self.visited.contains(&next_remaining) {
                     // Already searched.
                     continue;
                 }
 
-                let placed_piece_blocks = self.refs[bit.trailing_zeros() as usize];
+                let placed_piece_blocks = self.refs[index];
 
                 if let Some(placement) = placed_piece_blocks.place_according_to(board) {
                     if validator(&board, placement) == SearchResult::Pruned {
@@ -62,11 +64,11 @@ fn find_one_combination_minimized_dyn<'a>(
 
                     self.results.push(placed_piece_blocks);
 
-                    if next_remaining == 0 {
+                    if next_remaining.is_empty() {
                         return true;
                     }
 
-                    self.visited.insert(remaining);
+                    self.visited.insert(remaining.clone());
 
                     let mut next_board = board;
                     next_board.set_all(&placed_piece_blocks.locations);
@@ -82,17 +84,29 @@ fn find_one_combination_minimized_dyn<'a>(
         }
     }
 
-    let len = refs.len();
-    let mut builder = Builder {
-        refs,
-        results: Vec::with_capacity(len),
-        visited: FxHashSet::default(),
-    };
+    fn search<'b, M: RefsMask>(
+        initial_board: Board64,
+        refs: &Vec<&'b PlacedPieceBlocks>,
+        validator: &impl Fn(&Board64, BlPlacement) -> SearchResult,
+    ) -> Option<PlacedPieceBlocksFlow<'b>> {
+        let len = refs.len();
+        let mut builder = Builder::<M> {
+            refs,
+            results: Vec::with_capacity(len),
+            visited: FxHashSet::default(),
+        };
 
-    if builder.build(initial_board, (1u64 << len) - 1, &validator) {
-        Some(PlacedPieceBlocksFlow::new(initial_board, builder.results))
+        if builder.build(initial_board, M::filled(len), validator) {
+            Some(PlacedPieceBlocksFlow::new(initial_board, builder.results))
+        } else {
+            None
+        }
+    }
+
+    if refs.len() <= 64 {
+        search::<u64>(initial_board, refs, &validator)
     } else {
-        None
+        search::<RefsBitSet>(initial_board, refs, &validator)
     }
 }
 
@@ -111,31 +125,24 @@ fn find_one_dyn<'a, T>(
         return Some(PlacedPieceBlocksFlow::new(initial_board, refs.clone()));
     }
 
-    assert!(refs.len() < 64, "the refs length supports up to 64.");
-
     struct Builder<'a, 'b> {
         refs: &'a Vec<&'b PlacedPieceBlocks>,
         results: Vec<&'b PlacedPieceBlocks>,
     }
 
     impl<'b> Builder<'_, 'b> {
-        fn build<T>(
+        fn build<T, M: RefsMask>(
             &mut self,
             board: Board64,
-            remaining: u64,
+            remaining: M,
             validator: &impl Fn(&Board64, BlPlacement) -> SearchResult,
             prev_state: T,
             generator_next_state: &impl Fn(&T, &'b PlacedPieceBlocks) -> Option<T>,
         ) -> bool {
-            let mut candidates = remaining;
-            while 0 < candidates {
-                let next_candidates = candidates & (candidates - 1);
-                let bit = candidates - next_candidates;
-                let next_remaining = remaining - bit;
+            for index in remaining.indices() {
+                let next_remaining = remaining.without(index);
 
-                candidates = next_candidates;
-
-                let placed_piece_blocks = self.refs[bit.trailing_zeros() as usize];
+                let placed_piece_blocks = self.refs[index];
 
                 let next_fold =
                     if let Some(next) = generator_next_state(&prev_state, placed_piece_blocks) {
@@ -151,7 +158,7 @@ fn find_one_dyn<'a, T>(
 
                     self.results.push(placed_piece_blocks);
 
-                    if next_remaining == 0 {
+                    if next_remaining.is_empty() {
                         return true;
                     }
 
@@ -181,19 +188,173 @@ fn find_one_dyn<'a, T>(
         results: Vec::with_capacity(len),
     };
 
-    if builder.build(
-        initial_board,
-        (1u64 << len) - 1,
-        &validator,
-        initial_state,
-        &generator_next_state,
-    ) {
+    let found = if len <= 64 {
+        builder.build(
+            initial_board,
+            u64::filled(len),
+            &validator,
+            initial_state,
+            &generator_next_state,
+        )
+    } else {
+        builder.build(
+            initial_board,
+            RefsBitSet::filled(len),
+            &validator,
+            initial_state,
+            &generator_next_state,
+        )
+    };
+
+    if found {
         Some(PlacedPieceBlocksFlow::new(initial_board, builder.results))
     } else {
         None
     }
 }
 
+/// Returns a flow finds that all placements have been successful from the initial board.
+/// Placements are ordered according to the order of the shapes and the use of the holds.
+fn find_one_by_order<'a>(
+    initial_board: Board64,
+    refs: &Vec<&'a PlacedPieceBlocks>,
+    order: &[Shape],
+    allows_hold: bool,
+    validator: impl Fn(&Board64, BlPlacement) -> SearchResult,
+) -> Option<PlacedPieceBlocksFlow<'a>> {
+    find_one_dyn(
+        initial_board,
+        refs,
+        validator,
+        OrderCursor::from(order),
+        |prev, current| {
+            let shape = current.placed_piece.piece.shape;
+            prev.decide_next_op(&shape)
+                .filter(|&op| allows_hold || op == PopOp::First)
+                .map(|op| prev.pop(op).1)
+        },
+    )
+}
+
+/// Returns the validator that accepts the placements reachable from the spawn.
+fn stackable_validator<'b, T: RotationSystem>(
+    move_rules: &'b MoveRules<'b, T>,
+    spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+) -> impl Fn(&Board64, BlPlacement) -> SearchResult + 'b {
+    move |board, placement| {
+        let board_to_place = board.after_clearing();
+        if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
+            if move_rules.can_reach(placement, board_to_place, placement.piece.with(spawn)) {
+                return SearchResult::Success;
+            }
+        }
+        SearchResult::Pruned
+    }
+}
+
+/// It's similar to `stackable_validator()` except that the orientation is strictly checked.
+fn stackable_strictly_validator<'b, T: RotationSystem>(
+    move_rules: &'b MoveRules<'b, T>,
+    spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+) -> impl Fn(&Board64, BlPlacement) -> SearchResult + 'b {
+    move |board, placement| {
+        let board_to_place = board.after_clearing();
+        if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
+            if move_rules.can_reach_strictly(placement, board_to_place, placement.piece.with(spawn))
+            {
+                return SearchResult::Success;
+            }
+        }
+        SearchResult::Pruned
+    }
+}
+
+/// Returns the validator that prunes all placements once the watch stops.
+fn watched_validator<'b>(
+    watch: &'b CancelWatch<'b>,
+    validator: impl Fn(&Board64, BlPlacement) -> SearchResult + 'b,
+) -> impl Fn(&Board64, BlPlacement) -> SearchResult + 'b {
+    move |board, placement| {
+        if watch.should_stop() {
+            SearchResult::Pruned
+        } else {
+            validator(board, placement)
+        }
+    }
+}
+
+/// An iterator that yields each order to place all refs, in the same order as `for_each_dyn()`.
+/// It searches with an explicit stack instead of recursion.
+struct OrdersIter<'a, 'b, M: RefsMask, V: Fn(&Board64, BlPlacement) -> SearchResult> {
+    refs: &'b [&'a PlacedPieceBlocks],
+    validator: V,
+    // Each frame has the board, the refs not placed yet, and the refs not tried yet on the board.
+    stack: Vec<(Board64, M, M)>,
+    results: Vec<&'a PlacedPieceBlocks>,
+    // Whether to yield the empty order for the empty refs.
+    yields_empty: bool,
+}
+
+impl<'a, 'b, M: RefsMask, V: Fn(&Board64, BlPlacement) -> SearchResult> OrdersIter<'a, 'b, M, V> {
+    fn new(initial_board: Board64, refs: &'b [&'a PlacedPieceBlocks], validator: V) -> Self {
+        let remaining = M::filled(refs.len());
+        Self {
+            refs,
+            validator,
+            stack: vec![(initial_board, remaining.clone(), remaining)],
+            results: Vec::with_capacity(refs.len()),
+            yields_empty: refs.is_empty(),
+        }
+    }
+}
+
+impl<'a, M: RefsMask, V: Fn(&Board64, BlPlacement) -> SearchResult> Iterator
+    for OrdersIter<'a, '_, M, V>
+{
+    type Item = Vec<&'a PlacedPieceBlocks>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.yields_empty {
+            self.yields_empty = false;
+            self.stack.clear();
+            return Some(Vec::new());
+        }
+
+        while let Some((board, remaining, candidates)) = self.stack.last_mut() {
+            let Some(index) = candidates.first() else {
+                // All refs have been tried on the board.
+                self.stack.pop();
+                self.results.pop();
+                continue;
+            };
+            *candidates = candidates.without(index);
+
+            let placed_piece_blocks = self.refs[index];
+            let Some(placement) = placed_piece_blocks.place_according_to(*board) else {
+                continue;
+            };
+            if (self.validator)(board, placement) == SearchResult::Pruned {
+                continue;
+            }
+
+            let next_remaining = remaining.without(index);
+            if next_remaining.is_empty() {
+                let mut results = self.results.clone();
+                results.push(placed_piece_blocks);
+                return Some(results);
+            }
+
+            let mut next_board = *board;
+            next_board.set_all(&placed_piece_blocks.locations);
+            self.results.push(placed_piece_blocks);
+            self.stack
+                .push((next_board, next_remaining.clone(), next_remaining));
+        }
+
+        None
+    }
+}
+
 /// This holds the initial board and reference of the subsequent placed piece blocks.
 /// They are placed in order from the head.
 ///
@@ -248,15 +409,11 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         move_rules: &MoveRules<T>,
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
     ) -> Option<Self> {
-        find_one_combination_minimized_dyn(initial_board, refs, |board, placement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach(placement, board_to_place, placement.piece.with(spawn)) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        })
+        find_one_combination_minimized_dyn(
+            initial_board,
+            refs,
+            stackable_validator(move_rules, spawn_func),
+        )
     }
 
     /// It's similar to `find_one_stackable()` except that the orientation is strictly checked.
@@ -280,19 +437,59 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         move_rules: &MoveRules<T>,
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
     ) -> Option<Self> {
-        find_one_combination_minimized_dyn(initial_board, refs, |board, placement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach_strictly(
-                    placement,
-                    board_to_place,
-                    placement.piece.with(spawn),
-                ) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        })
+        find_one_combination_minimized_dyn(
+            initial_board,
+            refs,
+            stackable_strictly_validator(move_rules, spawn_func),
+        )
+    }
+
+    /// Returns a flow like `find_one_placeable()`, after checking the initial board with the pruning.
+    /// Returns None without searching if the initial board cannot be filled up to the height by the pieces of the refs.
+    /// See `PcPruning` for the details.
+    ///
+    /// The pruning is only this check on the initial board, and the boards on the way are not pruned.
+    /// The blocks of the refs are fixed, so if the refs fill the free cells of the initial board,
+    /// the remaining refs fill the free cells of each board on the way, and the pruning would never reject them.
+    /// To prune the partial boards, search the refs with `TilingSolver`, which rejects the free cells that cannot be covered.
+    /// ```
+    /// use std::str::FromStr;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let board = Board64::from_str("
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    /// ").unwrap();
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let refs = placed_piece_blocks.iter().collect();
+    ///
+    /// let flow = PlacedPieceBlocksFlow::find_one_placeable_with_pruning(board, &refs, &PcPruning::new(4));
+    /// assert!(flow.is_some());
+    ///
+    /// let flow = PlacedPieceBlocksFlow::find_one_placeable_with_pruning(board, &refs, &PcPruning::new(5));
+    /// assert!(flow.is_none());
+    /// ```
+    pub fn find_one_placeable_with_pruning(
+        initial_board: Board64,
+        refs: &Vec<&'a PlacedPieceBlocks>,
+        pruning: &PcPruning,
+    ) -> Option<Self> {
+        let shapes = refs
+            .iter()
+            .map(|it| it.placed_piece.piece.shape)
+            .collect::<Vec<_>>();
+        if pruning.validate(&initial_board, &shapes) == SearchResult::Pruned {
+            return None;
+        }
+        Self::find_one_placeable(initial_board, refs)
     }
 
     /// Returns a flow finds that all placements have been successful from the initial board.
@@ -307,31 +504,9 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         order: &[Shape],
         allows_hold: bool,
     ) -> Option<Self> {
-        if allows_hold {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                |_, _| SearchResult::Success,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape).map(|op| prev.pop(op).1)
-                },
-            )
-        } else {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                |_, _| SearchResult::Success,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape)
-                        .filter(|&op| op == PopOp::First)
-                        .map(|op| prev.pop(op).1)
-                },
-            )
-        }
+        find_one_by_order(initial_board, refs, order, allows_hold, |_, _| {
+            SearchResult::Success
+        })
     }
 
     /// Returns a flow finds that all placements have been successful from the initial board according to the Rotation System.
@@ -369,40 +544,8 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         move_rules: &MoveRules<T>,
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
     ) -> Option<Self> {
-        let validator = |board: &Board64, placement: BlPlacement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach(placement, board_to_place, placement.piece.with(spawn)) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        };
-        if allows_hold {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                validator,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape).map(|op| prev.pop(op).1)
-                },
-            )
-        } else {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                validator,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape)
-                        .filter(|&op| op == PopOp::First)
-                        .map(|op| prev.pop(op).1)
-                },
-            )
-        }
+        let validator = stackable_validator(move_rules, spawn_func);
+        find_one_by_order(initial_board, refs, order, allows_hold, validator)
     }
 
     /// It's similar to `find_one_stackable_by_order()` except that the orientation is strictly checked.
@@ -435,44 +578,8 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         move_rules: &MoveRules<T>,
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
     ) -> Option<Self> {
-        let validator = |board: &Board64, placement: BlPlacement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach_strictly(
-                    placement,
-                    board_to_place,
-                    placement.piece.with(spawn),
-                ) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        };
-        if allows_hold {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                validator,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape).map(|op| prev.pop(op).1)
-                },
-            )
-        } else {
-            Self::find_one_dyn(
-                initial_board,
-                refs,
-                validator,
-                OrderCursor::from(order),
-                |prev, current| {
-                    let shape = current.placed_piece.piece.shape;
-                    prev.decide_next_op(&shape)
-                        .filter(|&op| op == PopOp::First)
-                        .map(|op| prev.pop(op).1)
-                },
-            )
-        }
+        let validator = stackable_strictly_validator(move_rules, spawn_func);
+        find_one_by_order(initial_board, refs, order, allows_hold, validator)
     }
 
     /// The most generic `find_one` function.
@@ -498,6 +605,138 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         )
     }
 
+    /// Returns the validator of `find_one_stackable_dyn()` and the other stackable searches.
+    /// It accepts the placements reachable from the spawn, so it makes the searches that take a validator stackable.
+    #[inline]
+    pub fn stackable_validator<'b, T: RotationSystem>(
+        move_rules: &'b MoveRules<'b, T>,
+        spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+    ) -> impl Fn(&Board64, BlPlacement) -> SearchResult + 'b {
+        stackable_validator(move_rules, spawn_func)
+    }
+
+    /// It's similar to `stackable_validator()` except that the orientation is strictly checked.
+    #[inline]
+    pub fn stackable_strictly_validator<'b, T: RotationSystem>(
+        move_rules: &'b MoveRules<'b, T>,
+        spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+    ) -> impl Fn(&Board64, BlPlacement) -> SearchResult + 'b {
+        stackable_strictly_validator(move_rules, spawn_func)
+    }
+
+    /// It's similar to `find_one_placeable()` and `find_one_stackable()` except that the search stops when the token is cancelled.
+    /// The validator decides the placements to accept, such as `stackable_validator()`.
+    /// Returns None with the status `Cancelled` or `TimedOut` if the search stops before finding.
+    /// ```
+    /// use std::str::FromStr;
+    /// use std::time::Duration;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let board = Board64::from_str("
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    /// ").unwrap();
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let refs = placed_piece_blocks.iter().collect();
+    ///
+    /// let token = CancelToken::with_timeout(Duration::from_secs(60));
+    /// let placeable = |_: &Board64, _| SearchResult::Success;
+    /// let (flow, status) = PlacedPieceBlocksFlow::find_one_with_cancel(board, &refs, placeable, &token);
+    /// assert!(flow.is_some());
+    /// assert_eq!(status, SearchStatus::Completed);
+    ///
+    /// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let stackable = PlacedPieceBlocksFlow::stackable_validator(&move_rules, |_, _| Some(bl(4, 20)));
+    /// let (flow, status) = PlacedPieceBlocksFlow::find_one_with_cancel(board, &refs, stackable, &token);
+    /// assert!(flow.is_some());
+    /// assert_eq!(status, SearchStatus::Completed);
+    ///
+    /// token.cancel();
+    /// let (flow, status) = PlacedPieceBlocksFlow::find_one_with_cancel(board, &refs, placeable, &token);
+    /// assert!(flow.is_none());
+    /// assert_eq!(status, SearchStatus::Cancelled);
+    /// ```
+    pub fn find_one_with_cancel(
+        initial_board: Board64,
+        refs: &Vec<&'a PlacedPieceBlocks>,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult,
+        cancel: &CancelToken,
+    ) -> (Option<Self>, SearchStatus) {
+        let watch = CancelWatch::new(cancel);
+        let found = find_one_combination_minimized_dyn(
+            initial_board,
+            refs,
+            watched_validator(&watch, validator),
+        );
+        (found, watch.status())
+    }
+
+    /// It's similar to `find_one_dyn()` except that the search stops when the token is cancelled.
+    /// With `OrderCursor` as the state, it searches by order like `find_one_stackable_by_order()`.
+    /// See `find_one_with_cancel()` for the status.
+    /// ```
+    /// use std::str::FromStr;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let board = Board64::from_str("
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    ///     ...XXXXXXX
+    /// ").unwrap();
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let refs = placed_piece_blocks.iter().collect();
+    ///
+    /// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let order = [Shape::J, Shape::O, Shape::L];
+    /// let (flow, status) = PlacedPieceBlocksFlow::find_one_dyn_with_cancel(
+    ///     board,
+    ///     &refs,
+    ///     PlacedPieceBlocksFlow::stackable_validator(&move_rules, |_, _| Some(bl(4, 20))),
+    ///     OrderCursor::from(&order[..]),
+    ///     |cursor, current| {
+    ///         let op = cursor.decide_next_op(&current.placed_piece.piece.shape)?;
+    ///         Some(cursor.pop(op).1)
+    ///     },
+    ///     &CancelToken::new(),
+    /// );
+    /// assert_eq!(status, SearchStatus::Completed);
+    /// let shapes = flow.unwrap().refs.iter().map(|it| it.placed_piece.piece.shape).collect::<Vec<_>>();
+    /// assert_eq!(shapes, vec![Shape::J, Shape::O, Shape::L]);
+    /// ```
+    pub fn find_one_dyn_with_cancel<T>(
+        initial_board: Board64,
+        refs: &Vec<&'a PlacedPieceBlocks>,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult,
+        initial_state: T,
+        generator_next_state: impl Fn(&T, &'a PlacedPieceBlocks) -> Option<T>,
+        cancel: &CancelToken,
+    ) -> (Option<Self>, SearchStatus) {
+        let watch = CancelWatch::new(cancel);
+        let found = find_one_dyn(
+            initial_board,
+            refs,
+            watched_validator(&watch, validator),
+            initial_state,
+            generator_next_state,
+        );
+        (found, watch.status())
+    }
+
     /// It is visited once for every placement that meets the conditions from the initial board.
     ///
     ///
@@ -534,15 +773,7 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
         callback: C,
     ) {
-        self.for_each_dyn(callback, |board, placement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach(placement, board_to_place, placement.piece.with(spawn)) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        })
+        self.for_each_dyn(callback, stackable_validator(move_rules, spawn_func))
     }
 
     /// It's similar to `find_one_stackable()` except that the orientation is strictly checked.
@@ -570,19 +801,10 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition>,
         callback: C,
     ) {
-        self.for_each_dyn(callback, |board, placement| {
-            let board_to_place = board.after_clearing();
-            if let Some(spawn) = spawn_func(placement.piece, &board_to_place) {
-                if move_rules.can_reach_strictly(
-                    placement,
-                    board_to_place,
-                    placement.piece.with(spawn),
-                ) {
-                    return SearchResult::Success;
-                }
-            }
-            SearchResult::Pruned
-        })
+        self.for_each_dyn(
+            callback,
+            stackable_strictly_validator(move_rules, spawn_func),
+        )
     }
 
     #[inline]
@@ -596,8 +818,6 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
             return;
         }
 
-        assert!(self.refs.len() < 64, "the refs length supports up to 64.");
-
         struct Builder<'a, 'b, C: FnMut(&Vec<&PlacedPieceBlocks>) -> GenerateInstruction> {
             refs: &'a Vec<&'b PlacedPieceBlocks>,
             results: Vec<&'b PlacedPieceBlocks>,
@@ -605,21 +825,16 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
         }
 
         impl<C: FnMut(&Vec<&PlacedPieceBlocks>) -> GenerateInstruction> Builder<'_, '_, C> {
-            fn build(
+            fn build<M: RefsMask>(
                 &mut self,
                 board: Board64,
-                remaining: u64,
+                remaining: M,
                 validator: &impl Fn(&Board64, BlPlacement) -> SearchResult,
             ) -> GenerateInstruction {
-                let mut candidates = remaining;
-                while 0 < candidates {
-                    let next_candidates = candidates & (candidates - 1);
-                    let bit = candidates - next_candidates;
-                    let next_remaining = remaining - bit;
-
-                    candidates = next_candidates;
+                for index in remaining.indices() {
+                    let next_remaining = remaining.without(index);
 
-                    let placed_piece_blocks = self.refs[bit.trailing_zeros() as usize];
+                    let placed_piece_blocks = self.refs[index];
 
                     if let Some(placement) = placed_piece_blocks.place_according_to(board) {
                         if validator(&board, placement) == SearchResult::Pruned {
@@ -628,7 +843,7 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
 
                         self.results.push(placed_piece_blocks);
 
-                        if next_remaining == 0 {
+                        if next_remaining.is_empty() {
                             let instruction = (self.callback)(&self.results);
                             self.results.pop();
                             return instruction;
@@ -658,7 +873,377 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
             callback: &mut callback,
         };
 
-        builder.build(self.initial_board, (1u64 << len) - 1, &validator);
+        if len <= 64 {
+            builder.build(self.initial_board, u64::filled(len), &validator);
+        } else {
+            builder.build(self.initial_board, RefsBitSet::filled(len), &validator);
+        }
+    }
+
+    /// It's similar to `for_each_dyn()` except that the search stops when the token is cancelled.
+    /// Returns `Cancelled` or `TimedOut` if the search stops before all orders, otherwise `Completed`.
+    pub fn for_each_dyn_with_cancel<C: FnMut(&Vec<&PlacedPieceBlocks>) -> GenerateInstruction>(
+        &self,
+        mut callback: C,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult,
+        cancel: &CancelToken,
+    ) -> SearchStatus {
+        let watch = CancelWatch::new(cancel);
+        if watch.should_stop() {
+            return watch.status();
+        }
+
+        // The callback also checks the token, so that the search stops right after the callback cancels it.
+        self.for_each_dyn(
+            |order| {
+                let instruction = callback(order);
+                if instruction == GenerateInstruction::Stop || watch.should_stop() {
+                    GenerateInstruction::Stop
+                } else {
+                    GenerateInstruction::Continue
+                }
+            },
+            watched_validator(&watch, validator),
+        );
+        watch.status()
+    }
+
+    /// Returns an iterator of each order that all placements are successful from the initial board.
+    /// The orders are the same as `for_each_placeable()`, and they are searched lazily.
+    ///
+    /// Note that it does not depend on Rotation System. It depends only on spaces and landing.
+    /// ```
+    /// use std::str::FromStr;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let flow = PlacedPieceBlocksFlow::new(
+    ///     Board64::from_str("
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///     ").unwrap(),
+    ///     placed_piece_blocks.iter().collect(),
+    /// );
+    ///
+    /// let first = flow.iter_placeable().next().unwrap();
+    /// assert_eq!(first.len(), 3);
+    /// assert!(flow.iter_placeable().all(|order| order.last().unwrap().placed_piece.piece.shape == Shape::L));
+    /// ```
+    #[inline]
+    pub fn iter_placeable(&self) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + '_ {
+        self.iter_dyn(|_, _| SearchResult::Success)
+    }
+
+    /// It's similar to `iter_placeable()` except that the placements are checked by the Rotation System.
+    #[inline]
+    pub fn iter_stackable<'b, T: RotationSystem>(
+        &'b self,
+        move_rules: &'b MoveRules<T>,
+        spawn: BlPosition,
+    ) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + 'b {
+        self.iter_stackable_dyn(move_rules, move |_, _| Some(spawn))
+    }
+
+    /// It's similar to `iter_stackable()` except that spawn can be set dynamically.
+    #[inline]
+    pub fn iter_stackable_dyn<'b, T: RotationSystem>(
+        &'b self,
+        move_rules: &'b MoveRules<T>,
+        spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+    ) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + 'b {
+        self.iter_dyn(stackable_validator(move_rules, spawn_func))
+    }
+
+    /// It's similar to `iter_stackable()` except that the orientation is strictly checked.
+    #[inline]
+    pub fn iter_stackable_strictly<'b, T: RotationSystem>(
+        &'b self,
+        move_rules: &'b MoveRules<T>,
+        spawn: BlPosition,
+    ) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + 'b {
+        self.iter_stackable_strictly_dyn(move_rules, move |_, _| Some(spawn))
+    }
+
+    /// It's similar to `iter_stackable_strictly()` except that spawn can be set dynamically.
+    #[inline]
+    pub fn iter_stackable_strictly_dyn<'b, T: RotationSystem>(
+        &'b self,
+        move_rules: &'b MoveRules<T>,
+        spawn_func: impl Fn(Piece, &Board64) -> Option<BlPosition> + 'b,
+    ) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + 'b {
+        self.iter_dyn(stackable_strictly_validator(move_rules, spawn_func))
+    }
+
+    /// The most generic iterator. The validator works the same as `for_each_dyn()`.
+    #[inline]
+    pub fn iter_dyn<'b>(
+        &'b self,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult + 'b,
+    ) -> impl Iterator<Item = Vec<&'a PlacedPieceBlocks>> + 'b {
+        if self.refs.len() <= 64 {
+            Either::Left(OrdersIter::<u64, _>::new(
+                self.initial_board,
+                &self.refs,
+                validator,
+            ))
+        } else {
+            Either::Right(OrdersIter::<RefsBitSet, _>::new(
+                self.initial_board,
+                &self.refs,
+                validator,
+            ))
+        }
+    }
+
+    /// It's similar to `iter_dyn()` except that the search stops when the token is cancelled.
+    /// It yields `Ok` for each order, and then `Err` with the status at the end if the search stops before all orders.
+    /// ```
+    /// use std::str::FromStr;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let flow = PlacedPieceBlocksFlow::new(
+    ///     Board64::from_str("
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///     ").unwrap(),
+    ///     placed_piece_blocks.iter().collect(),
+    /// );
+    ///
+    /// let token = CancelToken::new();
+    /// let orders = flow.iter_dyn_with_cancel(|_, _| SearchResult::Success, &token).collect::<Vec<_>>();
+    /// assert!(orders.iter().all(|order| order.is_ok()));
+    /// assert_eq!(orders.len(), flow.iter_placeable().count());
+    ///
+    /// token.cancel();
+    /// let orders = flow.iter_dyn_with_cancel(|_, _| SearchResult::Success, &token).collect::<Vec<_>>();
+    /// assert_eq!(orders, vec![Err(SearchStatus::Cancelled)]);
+    /// ```
+    pub fn iter_dyn_with_cancel<'b>(
+        &'b self,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult + 'b,
+        cancel: &'b CancelToken,
+    ) -> impl Iterator<Item = Result<Vec<&'a PlacedPieceBlocks>, SearchStatus>> + 'b {
+        // The watch is shared by the validator and the iterator, which reports the status at the end.
+        let watch = Rc::new(CancelWatch::new(cancel));
+        let mut orders = {
+            let watch = Rc::clone(&watch);
+            self.iter_dyn(move |board, placement| {
+                if watch.should_stop() {
+                    SearchResult::Pruned
+                } else {
+                    validator(board, placement)
+                }
+            })
+        };
+
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            if !watch.should_stop() {
+                if let Some(order) = orders.next() {
+                    return Some(Ok(order));
+                }
+            }
+            finished = true;
+            let status = watch.status();
+            (!status.is_completed()).then_some(Err(status))
+        })
+    }
+
+    /// Returns all orders of `iter_placeable()`, searching on multiple threads. See `par_collect_dyn()` for the details.
+    #[cfg(feature = "parallel")]
+    pub fn par_collect_placeable(&self, threads: usize) -> Vec<Vec<&'a PlacedPieceBlocks>> {
+        self.par_collect_dyn(|_, _| SearchResult::Success, threads)
+    }
+
+    /// Returns all orders of `iter_stackable()`, searching on multiple threads. See `par_collect_dyn()` for the details.
+    #[cfg(feature = "parallel")]
+    pub fn par_collect_stackable<T: RotationSystem + Sync>(
+        &self,
+        move_rules: &MoveRules<T>,
+        spawn: BlPosition,
+        threads: usize,
+    ) -> Vec<Vec<&'a PlacedPieceBlocks>> {
+        self.par_collect_dyn(
+            stackable_validator(move_rules, move |_, _| Some(spawn)),
+            threads,
+        )
+    }
+
+    /// Returns all orders of `iter_dyn()`, searching on multiple threads.
+    /// The first three placements of the orders are expanded in advance, and the orders after each of them are searched as a task.
+    /// The orders are the same as `iter_dyn()` regardless of the count of threads. Zero threads use the available parallelism.
+    /// ```
+    /// use std::str::FromStr;
+    /// use tinyvec::array_vec;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    ///
+    /// let placed_piece_blocks = vec![
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(JW), 1, array_vec![0, 1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![1, 2])),
+    ///     PlacedPieceBlocks::make(PlacedPiece::new(piece!(LS), 0, array_vec![0, 3])),
+    /// ];
+    /// let flow = PlacedPieceBlocksFlow::new(
+    ///     Board64::from_str("
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///         ...XXXXXXX
+    ///     ").unwrap(),
+    ///     placed_piece_blocks.iter().collect(),
+    /// );
+    /// assert_eq!(flow.par_collect_placeable(4), flow.iter_placeable().collect::<Vec<_>>());
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn par_collect_dyn(
+        &self,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult + Sync,
+        threads: usize,
+    ) -> Vec<Vec<&'a PlacedPieceBlocks>> {
+        self.par_collect(validator, threads, &CancelToken::new()).0
+    }
+
+    /// It's similar to `par_collect_dyn()` except that the search stops when the token is cancelled.
+    /// All threads watch the same token. The orders found before the stop are the partial results,
+    /// and the returned status tells whether they're all.
+    #[cfg(feature = "parallel")]
+    pub fn par_collect_dyn_with_cancel(
+        &self,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult + Sync,
+        threads: usize,
+        cancel: &CancelToken,
+    ) -> (Vec<Vec<&'a PlacedPieceBlocks>>, SearchStatus) {
+        self.par_collect(validator, threads, cancel)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn par_collect(
+        &self,
+        validator: impl Fn(&Board64, BlPlacement) -> SearchResult + Sync,
+        threads: usize,
+        cancel: &CancelToken,
+    ) -> (Vec<Vec<&'a PlacedPieceBlocks>>, SearchStatus) {
+        // The prefixes of the orders are expanded level by level in ascending order of the refs,
+        // so that the tasks are in the same order as the sequential search.
+        let watch = CancelWatch::new(cancel);
+        let prefix_validator = watched_validator(&watch, &validator);
+        let mut prefixes = vec![(
+            self.initial_board,
+            Vec::<&'a PlacedPieceBlocks>::new(),
+            (0..self.refs.len()).collect::<Vec<_>>(),
+        )];
+        for _ in 0..PAR_SPLIT_DEPTH.min(self.refs.len()) {
+            prefixes = prefixes
+                .into_iter()
+                .flat_map(|(board, prefix, remaining)| {
+                    let validator = &prefix_validator;
+                    remaining
+                        .iter()
+                        .filter_map(|&index| {
+                            let current = self.refs[index];
+                            let placement = current.place_according_to(board)?;
+                            if validator(&board, placement) == SearchResult::Pruned {
+                                return None;
+                            }
+
+                            let mut next_board = board;
+                            next_board.set_all(&current.locations);
+                            let mut next_prefix = prefix.clone();
+                            next_prefix.push(current);
+                            let next_remaining = remaining
+                                .iter()
+                                .copied()
+                                .filter(|&it| it != index)
+                                .collect();
+                            Some((next_board, next_prefix, next_remaining))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+        if !watch.status().is_completed() {
+            return (Vec::new(), watch.status());
+        }
+
+        // Each task has its own watch of the token, and the first stop is reported.
+        let results = crate::parallel::map(&prefixes, threads, |(board, prefix, remaining)| {
+            let watch = CancelWatch::new(cancel);
+            let rest = remaining.iter().map(|&index| self.refs[index]).collect();
+            let orders = PlacedPieceBlocksFlow::new(*board, rest)
+                .iter_dyn(watched_validator(&watch, &validator))
+                .map(|order| prefix.iter().copied().chain(order).collect())
+                .collect::<Vec<_>>();
+            (orders, watch.status())
+        });
+
+        let status = results
+            .iter()
+            .map(|(_, status)| *status)
+            .find(|status| !status.is_completed())
+            .unwrap_or(SearchStatus::Completed);
+        let orders = results.into_iter().flat_map(|(orders, _)| orders).collect();
+        (orders, status)
+    }
+
+    /// Returns the results of `find_one_stackable()` for each refs, searching on multiple threads.
+    /// It's useful to check the stackability of the tilings from `TilingSolver`.
+    /// The results are in the order of the refs. Zero threads use the available parallelism.
+    #[cfg(feature = "parallel")]
+    pub fn par_find_one_stackable_each<T: RotationSystem + Sync>(
+        initial_board: Board64,
+        refs_list: &[Vec<&'a PlacedPieceBlocks>],
+        move_rules: &MoveRules<'a, T>,
+        spawn: BlPosition,
+        threads: usize,
+    ) -> Vec<Option<Self>> {
+        crate::parallel::map(refs_list, threads, |refs| {
+            Self::find_one_stackable(initial_board, refs, move_rules, spawn)
+        })
+    }
+
+    /// It's similar to `par_find_one_stackable_each()` except that the searches stop when the token is cancelled.
+    /// The refs not searched to the end have None, and the returned status is of the first stop.
+    #[cfg(feature = "parallel")]
+    pub fn par_find_one_stackable_each_with_cancel<T: RotationSystem + Sync>(
+        initial_board: Board64,
+        refs_list: &[Vec<&'a PlacedPieceBlocks>],
+        move_rules: &MoveRules<'a, T>,
+        spawn: BlPosition,
+        threads: usize,
+        cancel: &CancelToken,
+    ) -> (Vec<Option<Self>>, SearchStatus) {
+        let results = crate::parallel::map(refs_list, threads, |refs| {
+            let validator = stackable_validator(move_rules, move |_, _| Some(spawn));
+            Self::find_one_with_cancel(initial_board, refs, validator, cancel)
+        });
+
+        let status = results
+            .iter()
+            .map(|(_, status)| *status)
+            .find(|status| !status.is_completed())
+            .unwrap_or(SearchStatus::Completed);
+        let flows = results.into_iter().map(|(flow, _)| flow).collect();
+        (flows, status)
     }
 
     #[inline]
@@ -902,7 +1487,9 @@ impl<'a> PlacedPieceBlocksFlow<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
     use std::str::FromStr;
+    use std::time::Duration;
 
     use tinyvec::array_vec;
 
@@ -918,6 +1505,293 @@ mod tests {
         assert!(placed_piece_flow.can_stack_all_strictly(&MoveRules::default(), bl(4, 20)));
     }
 
+    #[test]
+    fn refs_of_64() {
+        // 64 O's are the most on the fast path.
+        let placed_piece_blocks = (0..64u8)
+            .map(|index| {
+                let (pair, x) = (index / 5, index % 5);
+                PlacedPieceBlocks::make(PlacedPiece::new(
+                    piece!(ON),
+                    x * 2,
+                    array_vec![pair * 2, pair * 2 + 1],
+                ))
+            })
+            .collect::<Vec<_>>();
+        let refs = placed_piece_blocks.iter().collect::<Vec<_>>();
+
+        let flow = PlacedPieceBlocksFlow::find_one_placeable(Board64::blank(), &refs).unwrap();
+        assert_eq!(flow.refs, refs);
+
+        let flow = PlacedPieceBlocksFlow::new(Board64::blank(), refs.clone());
+        assert_eq!(flow.iter_placeable().next(), Some(refs.clone()));
+
+        let mut visited = 0;
+        flow.for_each_placeable(|results| {
+            assert_eq!(results.len(), 64);
+            visited += 1;
+            GenerateInstruction::Stop
+        });
+        assert_eq!(visited, 1);
+    }
+
+    #[test]
+    fn long_refs() {
+        // 70 O's fill 28 lines, beyond 64 refs.
+        let placed_piece_blocks = (0..14u8)
+            .flat_map(|pair| {
+                (0..5u8).map(move |index| {
+                    PlacedPieceBlocks::make(PlacedPiece::new(
+                        piece!(ON),
+                        index * 2,
+                        array_vec![pair * 2, pair * 2 + 1],
+                    ))
+                })
+            })
+            .collect::<Vec<_>>();
+        let refs = placed_piece_blocks.iter().collect::<Vec<_>>();
+        assert_eq!(refs.len(), 70);
+
+        let flow = PlacedPieceBlocksFlow::find_one_placeable(Board64::blank(), &refs).unwrap();
+        assert_eq!(flow.refs, refs);
+        assert!(flow.can_place_all());
+
+        let flow = PlacedPieceBlocksFlow::find_one_placeable_by_order(
+            Board64::blank(),
+            &refs,
+            &[Shape::O; 70],
+            false,
+        )
+        .unwrap();
+        assert_eq!(flow.refs, refs);
+
+        let mut visited = 0;
+        PlacedPieceBlocksFlow::new(Board64::blank(), refs.clone()).for_each_placeable(|results| {
+            assert_eq!(results.len(), 70);
+            visited += 1;
+            GenerateInstruction::Stop
+        });
+        assert_eq!(visited, 1);
+
+        let flow = PlacedPieceBlocksFlow::new(Board64::blank(), refs.clone());
+        assert_eq!(flow.iter_placeable().next(), Some(refs.clone()));
+    }
+
+    #[test]
+    fn cancel() {
+        // The orders of 70 O's are too many to search all.
+        let placed_piece_blocks = (0..14u8)
+            .flat_map(|pair| {
+                (0..5u8).map(move |index| {
+                    PlacedPieceBlocks::make(PlacedPiece::new(
+                        piece!(ON),
+                        index * 2,
+                        array_vec![pair * 2, pair * 2 + 1],
+                    ))
+                })
+            })
+            .collect::<Vec<_>>();
+        let refs = placed_piece_blocks.iter().collect::<Vec<_>>();
+        let flow = PlacedPieceBlocksFlow::new(Board64::blank(), refs.clone());
+        let placeable = |_: &Board64, _| SearchResult::Success;
+
+        // The search stops right after the callback cancels the token.
+        let token = CancelToken::new();
+        let mut visited = 0;
+        let status = flow.for_each_dyn_with_cancel(
+            |_| {
+                visited += 1;
+                if visited == 3 {
+                    token.cancel();
+                }
+                GenerateInstruction::Continue
+            },
+            placeable,
+            &token,
+        );
+        assert_eq!((visited, status), (3, SearchStatus::Cancelled));
+
+        // The last placement is always rejected, so no flows are found before the cancel.
+        let token = CancelToken::new();
+        let calls = Cell::new(0);
+        let (found, status) = PlacedPieceBlocksFlow::find_one_dyn_with_cancel(
+            Board64::blank(),
+            &refs,
+            placeable,
+            0,
+            |&depth, _| {
+                calls.set(calls.get() + 1);
+                if calls.get() == 10_000 {
+                    token.cancel();
+                }
+                (depth + 1 < refs.len()).then_some(depth + 1)
+            },
+            &token,
+        );
+        assert_eq!((found, status), (None, SearchStatus::Cancelled));
+
+        // The deadline is read when the searches start.
+        let token = CancelToken::with_timeout(Duration::ZERO);
+        let mut visited = 0;
+        let status = flow.for_each_dyn_with_cancel(
+            |_| {
+                visited += 1;
+                GenerateInstruction::Continue
+            },
+            placeable,
+            &token,
+        );
+        assert_eq!((visited, status), (0, SearchStatus::TimedOut));
+        let (found, status) =
+            PlacedPieceBlocksFlow::find_one_with_cancel(Board64::blank(), &refs, placeable, &token);
+        assert_eq!((found, status), (None, SearchStatus::TimedOut));
+
+        // Not stopped if completed.
+        let token = CancelToken::with_timeout(Duration::from_secs(60));
+        let (found, status) =
+            PlacedPieceBlocksFlow::find_one_with_cancel(Board64::blank(), &refs, placeable, &token);
+        assert_eq!(found.unwrap().refs, refs);
+        assert_eq!(status, SearchStatus::Completed);
+    }
+
+    #[test]
+    fn cancel_with_validators() {
+        let placed_piece_blocks = (0..5u8)
+            .map(|index| {
+                PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), index * 2, array_vec![0, 1]))
+            })
+            .collect::<Vec<_>>();
+        let refs = placed_piece_blocks.iter().collect::<Vec<_>>();
+        let board = Board64::blank();
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+        let spawn = bl(4, 20);
+        let order = [Shape::O; 5];
+        let flow = PlacedPieceBlocksFlow::new(board, refs.clone());
+        let stackable =
+            || PlacedPieceBlocksFlow::stackable_validator(&move_rules, |_, _| Some(spawn));
+        let stackable_strictly =
+            || PlacedPieceBlocksFlow::stackable_strictly_validator(&move_rules, |_, _| Some(spawn));
+
+        let cancelled = CancelToken::new();
+        cancelled.cancel();
+        for (token, expected) in [
+            (CancelToken::new(), SearchStatus::Completed),
+            (cancelled, SearchStatus::Cancelled),
+        ] {
+            let completed = expected.is_completed();
+            let (found, status) = PlacedPieceBlocksFlow::find_one_with_cancel(
+                board,
+                &refs,
+                stackable_strictly(),
+                &token,
+            );
+            assert_eq!((found.is_some(), status), (completed, expected));
+
+            let (found, status) = PlacedPieceBlocksFlow::find_one_dyn_with_cancel(
+                board,
+                &refs,
+                stackable(),
+                OrderCursor::from(&order[..]),
+                |cursor, current| {
+                    let op = cursor.decide_next_op(&current.placed_piece.piece.shape)?;
+                    Some(cursor.pop(op).1)
+                },
+                &token,
+            );
+            assert_eq!((found.is_some(), status), (completed, expected));
+
+            let mut visited = 0;
+            let status = flow.for_each_dyn_with_cancel(
+                |_| {
+                    visited += 1;
+                    GenerateInstruction::Continue
+                },
+                stackable_strictly(),
+                &token,
+            );
+            assert_eq!(status, expected);
+            assert_eq!(visited, if completed { 120 } else { 0 });
+
+            let orders = flow
+                .iter_dyn_with_cancel(stackable(), &token)
+                .collect::<Vec<_>>();
+            if completed {
+                let expected_orders = flow.iter_stackable(&move_rules, spawn).map(Ok);
+                assert!(orders.into_iter().eq(expected_orders));
+            } else {
+                assert_eq!(orders, vec![Err(SearchStatus::Cancelled)]);
+            }
+
+            #[cfg(feature = "parallel")]
+            {
+                let (orders, status) = flow.par_collect_dyn_with_cancel(stackable(), 2, &token);
+                assert_eq!(status, expected);
+                assert_eq!(orders.len(), if completed { 120 } else { 0 });
+
+                let (flows, status) =
+                    PlacedPieceBlocksFlow::par_find_one_stackable_each_with_cancel(
+                        board,
+                        &[refs.clone(), refs[..2].to_vec()],
+                        &move_rules,
+                        spawn,
+                        2,
+                        &token,
+                    );
+                assert_eq!(status, expected);
+                assert!(flows.iter().all(|flow| flow.is_some() == completed));
+            }
+        }
+
+        // The iterator stops after the orders before the cancel.
+        let token = CancelToken::new();
+        let mut orders = flow.iter_dyn_with_cancel(|_, _| SearchResult::Success, &token);
+        assert!(orders.next().unwrap().is_ok());
+        assert!(orders.next().unwrap().is_ok());
+        token.cancel();
+        assert_eq!(orders.next(), Some(Err(SearchStatus::Cancelled)));
+        assert_eq!(orders.next(), None);
+    }
+
+    #[test]
+    fn find_one_placeable_with_pruning() {
+        let board = Board64::from_str(
+            "
+            ...#######
+            ...#######
+            ##.#######
+            ##.#######
+        ",
+        )
+        .unwrap();
+        let placed_piece_blocks = [
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(IE), 2, array_vec![0, 1, 2, 3])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(ON), 0, array_vec![2, 3])),
+        ];
+        let refs = placed_piece_blocks.iter().collect();
+
+        let expected = PlacedPieceBlocksFlow::find_one_placeable(board, &refs);
+        assert!(expected.is_some());
+        assert_eq!(
+            PlacedPieceBlocksFlow::find_one_placeable_with_pruning(
+                board,
+                &refs,
+                &PcPruning::new(4)
+            ),
+            expected,
+        );
+
+        // The free cells don't match the pieces.
+        let refs = vec![&placed_piece_blocks[0]];
+        assert_eq!(
+            PlacedPieceBlocksFlow::find_one_placeable_with_pruning(
+                board,
+                &refs,
+                &PcPruning::new(4)
+            ),
+            None,
+        );
+    }
+
     #[test]
     fn new_case1() {
         let board = Board64::from_str(
@@ -1518,7 +2392,7 @@ mod tests {
         ",
         )
         .unwrap();
-        let placed_piece_blocks = vec![
+        let placed_piece_blocks = [
             PlacedPieceBlocks::make(PlacedPiece::new(piece!(SN), 3, array_vec![0, 1])),
             PlacedPieceBlocks::make(PlacedPiece::new(piece!(IN), 0, array_vec![1])),
             PlacedPieceBlocks::make(PlacedPiece::new(piece!(TN), 0, array_vec![2, 3])),
@@ -1616,4 +2490,138 @@ mod tests {
             assert_eq!(counter, 0);
         }
     }
+
+    #[test]
+    fn iter_same_as_for_each() {
+        let board = Board64::from_str(
+            "
+            ....####..
+            ....######
+            ....######
+            ....###..#
+        ",
+        )
+        .unwrap();
+        let placed_piece_blocks = [
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(LE), 0, array_vec![0, 1, 2])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(JS), 0, array_vec![2, 3])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(SW), 1, array_vec![0, 1, 2])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(IW), 3, array_vec![0, 1, 2, 3])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(SN), 7, array_vec![0, 3])),
+        ];
+        let flow = PlacedPieceBlocksFlow::new(board, placed_piece_blocks.iter().collect());
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+
+        let keys = |order: &Vec<&PlacedPieceBlocks>| {
+            order.iter().map(|it| it.placed_piece).collect::<Vec<_>>()
+        };
+
+        let mut expected = Vec::new();
+        flow.for_each_placeable(|order| {
+            expected.push(keys(order));
+            GenerateInstruction::Continue
+        });
+        assert!(!expected.is_empty());
+        assert_eq!(
+            flow.iter_placeable()
+                .map(|order| keys(&order))
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let mut expected = Vec::new();
+        flow.for_each_stackable(&move_rules, bl(4, 20), |order| {
+            expected.push(keys(order));
+            GenerateInstruction::Continue
+        });
+        assert_eq!(expected.len(), 4);
+        assert_eq!(
+            flow.iter_stackable(&move_rules, bl(4, 20))
+                .map(|order| keys(&order))
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            flow.iter_stackable(&move_rules, bl(4, 20)).take(2).count(),
+            2
+        );
+
+        assert_eq!(
+            flow.iter_stackable_strictly(&move_rules, bl(4, 20)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn iter_empty() {
+        let flow = PlacedPieceBlocksFlow::new(Board64::blank(), vec![]);
+        assert_eq!(
+            flow.iter_placeable().collect::<Vec<_>>(),
+            vec![Vec::<&PlacedPieceBlocks>::new()]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel() {
+        let board = Board64::from_str(
+            "
+            ....####..
+            ....######
+            ....######
+            ....###..#
+        ",
+        )
+        .unwrap();
+        let placed_piece_blocks = [
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(LE), 0, array_vec![0, 1, 2])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(JS), 0, array_vec![2, 3])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(SW), 1, array_vec![0, 1, 2])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(IW), 3, array_vec![0, 1, 2, 3])),
+            PlacedPieceBlocks::make(PlacedPiece::new(piece!(SN), 7, array_vec![0, 3])),
+        ];
+        let refs = placed_piece_blocks.iter().collect::<Vec<_>>();
+        let flow = PlacedPieceBlocksFlow::new(board, refs.clone());
+        let move_rules = MoveRules::srs(AllowMove::Softdrop);
+
+        let placeable = flow.iter_placeable().collect::<Vec<_>>();
+        let stackable = flow
+            .iter_stackable(&move_rules, bl(4, 20))
+            .collect::<Vec<_>>();
+        assert_eq!(stackable.len(), 4);
+        for threads in [0, 1, 3] {
+            assert_eq!(flow.par_collect_placeable(threads), placeable);
+            assert_eq!(
+                flow.par_collect_stackable(&move_rules, bl(4, 20), threads),
+                stackable
+            );
+        }
+
+        // Shorter than the placements expanded before splitting.
+        for len in 0..=3 {
+            let flow = PlacedPieceBlocksFlow::new(board, refs[..len].to_vec());
+            assert_eq!(
+                flow.par_collect_placeable(2),
+                flow.iter_placeable().collect::<Vec<_>>()
+            );
+        }
+
+        let refs_list = vec![refs.clone(), refs[..4].to_vec(), vec![]];
+        let expected = refs_list
+            .iter()
+            .map(|refs| {
+                PlacedPieceBlocksFlow::find_one_stackable(board, refs, &move_rules, bl(4, 20))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            PlacedPieceBlocksFlow::par_find_one_stackable_each(
+                board,
+                &refs_list,
+                &move_rules,
+                bl(4, 20),
+                2
+            ),
+            expected
+        );
+    }
 }
@@ -0,0 +1,392 @@
+use std::ops;
+
+use crate::coordinates::cc;
+use crate::internal_moves::MoveBits;
+use crate::pieces::{Orientation, Shape};
+use crate::placements::{BlPlacement, CcPlacement};
+use crate::With;
+
+const ORIENTATIONS: [Orientation; 4] = [
+    Orientation::North,
+    Orientation::East,
+    Orientation::South,
+    Orientation::West,
+];
+
+/// A set of placements of one shape, held as bits.
+///
+/// `cols[orientation][x]` has a bit for each row, and bit `y` represents the placement at `cc(x, y)` in the orientation.
+/// It's the same form as the board, so the bit width is the height of the board it was generated from.
+///
+/// It does not allocate, so it's suitable for searches that generate many moves.
+/// ```
+/// use bitris::piece;
+/// use bitris::prelude::*;
+/// let rules = MoveRules::srs(AllowMove::Softdrop);
+/// let set = rules.generate_minimized_moves_set(Board64::blank(), piece!(ON).with(bl(4, 20)));
+/// assert_eq!(set.len(), 9);
+/// assert!(set.contains(piece!(ON).with(bl(0, 0))));
+/// assert!(!set.contains(piece!(ON).with(bl(0, 1))));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PlacementSet<T: MoveBits> {
+    pub shape: Shape,
+    pub cols: [[T; 10]; 4],
+}
+
+impl<T: MoveBits> PlacementSet<T> {
+    #[inline]
+    pub const fn new(shape: Shape, cols: [[T; 10]; 4]) -> Self {
+        Self { shape, cols }
+    }
+
+    /// Returns an empty set.
+    #[inline]
+    pub const fn blank(shape: Shape) -> Self {
+        Self::new(shape, [[T::ZERO; 10]; 4])
+    }
+
+    /// Returns true if the placement is in the set.
+    /// Placements of other shapes or outside the board are never contained.
+    #[inline]
+    pub fn contains(&self, placement: impl Into<CcPlacement>) -> bool {
+        match self.index_of(placement.into()) {
+            Some((orientation, x, y)) => self.cols[orientation][x] & (T::ONE << y) != T::ZERO,
+            None => false,
+        }
+    }
+
+    /// Adds the placement to the set.
+    /// Returns true if the placement was newly inserted.
+    ///
+    /// Panics if the placement is another shape or outside the board.
+    #[inline]
+    pub fn insert(&mut self, placement: impl Into<CcPlacement>) -> bool {
+        let placement = placement.into();
+        let (orientation, x, y) = self
+            .index_of(placement)
+            .unwrap_or_else(|| panic!("the placement cannot be inserted: {}", placement));
+        let bit = T::ONE << y;
+        let inserted = self.cols[orientation][x] & bit == T::ZERO;
+        self.cols[orientation][x] |= bit;
+        inserted
+    }
+
+    /// Removes the placement from the set.
+    /// Returns true if the placement was present.
+    #[inline]
+    pub fn remove(&mut self, placement: impl Into<CcPlacement>) -> bool {
+        match self.index_of(placement.into()) {
+            Some((orientation, x, y)) => {
+                let bit = T::ONE << y;
+                let removed = self.cols[orientation][x] & bit != T::ZERO;
+                self.cols[orientation][x] &= !bit;
+                removed
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the count of placements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cols
+            .iter()
+            .flatten()
+            .map(|&col| col.count_ones() as usize)
+            .sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cols.iter().flatten().all(|&col| col == T::ZERO)
+    }
+
+    /// Returns an iterator of the placements in the order of orientation, x and y.
+    #[inline]
+    pub fn iter(&self) -> PlacementSetIter<T> {
+        PlacementSetIter {
+            shape: self.shape,
+            cols: self.cols,
+            index: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Appends the placements grouped by the position, for the shapes that look the same in all orientations.
+    /// The positions are in the order of x and y in the reference orientation, and each is followed by all orientations.
+    #[inline]
+    pub(crate) fn extend_grouped_by_position(
+        &self,
+        reference: Orientation,
+        out: &mut Vec<BlPlacement>,
+    ) {
+        let piece = self.shape.with(reference);
+        for (x, &col) in self.cols[reference as usize].iter().enumerate() {
+            let mut col = col;
+            while col != T::ZERO {
+                let y = col.trailing_zeros();
+                col -= T::ONE << y;
+
+                let position = piece
+                    .with(cc(x as i32, y as i32))
+                    .to_bl_placement()
+                    .position;
+                out.extend(
+                    ORIENTATIONS.map(|orientation| self.shape.with(orientation).with(position)),
+                );
+            }
+        }
+    }
+
+    #[inline]
+    fn index_of(&self, placement: CcPlacement) -> Option<(usize, usize, u32)> {
+        let location = placement.position.to_location();
+        if placement.piece.shape != self.shape
+            || !(0..10).contains(&location.x)
+            || !(0..T::BITS as i32).contains(&location.y)
+        {
+            return None;
+        }
+        Some((
+            placement.piece.orientation as usize,
+            location.x as usize,
+            location.y as u32,
+        ))
+    }
+}
+
+macro_rules! placement_set_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, |$a:ident, $b:ident| $f:expr) => {
+        impl<T: MoveBits> ops::$op<PlacementSet<T>> for PlacementSet<T> {
+            type Output = PlacementSet<T>;
+
+            #[inline]
+            fn $method(mut self, rhs: PlacementSet<T>) -> Self::Output {
+                ops::$op_assign::$method_assign(&mut self, rhs);
+                self
+            }
+        }
+
+        impl<T: MoveBits> ops::$op_assign<PlacementSet<T>> for PlacementSet<T> {
+            #[inline]
+            fn $method_assign(&mut self, rhs: PlacementSet<T>) {
+                debug_assert_eq!(self.shape, rhs.shape);
+                for (left, right) in self.cols.iter_mut().zip(rhs.cols) {
+                    for (l, r) in left.iter_mut().zip(right) {
+                        let ($a, $b) = (*l, r);
+                        *l = $f;
+                    }
+                }
+            }
+        }
+    };
+}
+
+// Union
+placement_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+// Intersection
+placement_set_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+// Symmetric difference
+placement_set_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+// Difference
+placement_set_op!(Sub, sub, SubAssign, sub_assign, |a, b| a & !b);
+
+impl<T: MoveBits> IntoIterator for PlacementSet<T> {
+    type Item = BlPlacement;
+    type IntoIter = PlacementSetIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: MoveBits> IntoIterator for &PlacementSet<T> {
+    type Item = BlPlacement;
+    type IntoIter = PlacementSetIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator of the placements in `PlacementSet`.
+#[derive(Clone, Debug)]
+pub struct PlacementSetIter<T: MoveBits> {
+    shape: Shape,
+    cols: [[T; 10]; 4],
+    // orientation * 10 + x
+    index: usize,
+    len: usize,
+}
+
+impl<T: MoveBits> Iterator for PlacementSetIter<T> {
+    type Item = BlPlacement;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < 40 {
+            let (orientation, x) = (self.index / 10, self.index % 10);
+            let col = &mut self.cols[orientation][x];
+            if *col == T::ZERO {
+                self.index += 1;
+                continue;
+            }
+
+            let y = col.trailing_zeros();
+            *col -= T::ONE << y;
+            self.len -= 1;
+
+            let piece = self.shape.with(ORIENTATIONS[orientation]);
+            return Some(piece.with(cc(x as i32, y as i32)).to_bl_placement());
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: MoveBits> ExactSizeIterator for PlacementSetIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::piece;
+    use crate::prelude::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = PlacementSet::<u64>::blank(Shape::T);
+        assert!(set.is_empty());
+
+        assert!(set.insert(piece!(TN).with(bl(0, 0))));
+        assert!(!set.insert(piece!(TN).with(cc(1, 0))));
+        assert!(set.insert(piece!(TE).with(bl(0, 61))));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(piece!(TN).with(bl(0, 0))));
+        assert!(!set.contains(piece!(TS).with(bl(0, 0))));
+        assert!(!set.contains(piece!(ON).with(bl(0, 0))));
+        assert!(!set.contains(piece!(TN).with(cc(-1, 0))));
+
+        assert!(set.remove(piece!(TN).with(bl(0, 0))));
+        assert!(!set.remove(piece!(TN).with(bl(0, 0))));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![piece!(TE).with(bl(0, 61))]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_other_shape() {
+        let mut set = PlacementSet::<u8>::blank(Shape::T);
+        set.insert(piece!(IN).with(bl(0, 0)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let board = Board64::from_str(
+            "\
+            ..........\
+            ###...####\
+            ##.....###\
+            ",
+        )
+        .unwrap();
+        let spawn = piece!(TN).with(bl(4, 20));
+        let softdrop = MoveRules::srs(AllowMove::Softdrop).generate_all_moves_set(board, spawn);
+        let harddrop = MoveRules::srs(AllowMove::Harddrop).generate_all_moves_set(board, spawn);
+        assert_eq!(softdrop.len(), 36);
+        assert_eq!(harddrop.len(), 34);
+
+        assert_eq!(softdrop | harddrop, softdrop);
+        assert_eq!(softdrop & harddrop, harddrop);
+        assert_eq!((softdrop - harddrop).len(), 2);
+        assert_eq!(softdrop ^ harddrop, softdrop - harddrop);
+        assert!((harddrop - softdrop).is_empty());
+
+        let only_softdrop = softdrop - harddrop;
+        assert!(only_softdrop.contains(piece!(TN).with(bl(2, 0))));
+    }
+
+    #[test]
+    fn iter_is_same_as_vec() {
+        let board = Board64::from_str(
+            "\
+            ..........\
+            #.......##\
+            ##..#..###\
+            ",
+        )
+        .unwrap();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        for shape in Shape::all_iter() {
+            let spawn = shape.with(Orientation::North).with(bl(4, 20));
+
+            let set = rules.generate_all_moves_set(board, spawn);
+            let moves = rules.generate_all_moves(board, spawn);
+            assert_eq!(set.iter().len(), moves.len());
+            assert!(moves.iter().all(|&placement| set.contains(placement)));
+            if shape != Shape::O {
+                assert_eq!(set.into_iter().collect::<Vec<_>>(), moves);
+            }
+
+            let set = rules.generate_minimized_moves_set(board, spawn);
+            let moves = rules.generate_minimized_moves(board, spawn);
+            assert_eq!(set.iter().collect::<Vec<_>>(), moves);
+        }
+    }
+
+    #[test]
+    fn all_moves_of_o_grouped_by_position() {
+        let board = Board64::from_str(
+            "\
+            #.......##\
+            ##..#..###\
+            ",
+        )
+        .unwrap();
+        for rules in [
+            MoveRules::srs(AllowMove::Softdrop),
+            MoveRules::srs(AllowMove::Harddrop),
+            MoveRules::srs(AllowMove::TwentyG),
+        ] {
+            let spawn = piece!(ON).with(bl(4, 20));
+            let moves = rules.generate_all_moves(board, spawn);
+            let minimized = rules.generate_minimized_moves(board, spawn);
+            assert_eq!(moves.len(), minimized.len() * 4);
+
+            // Each position is followed by all orientations, as generated before `PlacementSet`.
+            for (chunk, placement) in moves.chunks(4).zip(minimized) {
+                let expected = Orientation::all_iter()
+                    .map(|orientation| Shape::O.with(orientation).with(placement.position))
+                    .collect::<Vec<_>>();
+                assert_eq!(chunk, expected);
+            }
+            assert_eq!(rules.try_generate_all_moves(board, spawn), Ok(moves));
+        }
+    }
+
+    #[test]
+    fn fill_into_buffer() {
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let mut buffer = Vec::with_capacity(128);
+
+        rules.generate_all_moves_into(Board64::blank(), piece!(TN).with(bl(4, 20)), &mut buffer);
+        assert_eq!(buffer.len(), 34);
+
+        buffer.clear();
+        rules.generate_minimized_moves_into(
+            Board8::blank(),
+            piece!(IN).with(bl(3, 6)),
+            &mut buffer,
+        );
+        assert_eq!(buffer.len(), 17);
+    }
+}
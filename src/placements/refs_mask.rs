@@ -0,0 +1,150 @@
+use std::hash::Hash;
+
+/// The set of the indices of the refs not placed yet in the flow searches.
+///
+/// `u64` is the fast path for the refs up to 64, and `RefsBitSet` supports any length.
+pub(crate) trait RefsMask: Clone + Eq + Hash {
+    /// Returns the mask containing `0..len`.
+    fn filled(len: usize) -> Self;
+
+    fn is_empty(&self) -> bool;
+
+    /// Returns the mask without the index.
+    fn without(&self, index: usize) -> Self;
+
+    /// Returns the lowest index, or None if empty.
+    fn first(&self) -> Option<usize>;
+
+    /// Returns an iterator of the indices in ascending order.
+    fn indices(&self) -> impl Iterator<Item = usize> + '_;
+}
+
+impl RefsMask for u64 {
+    #[inline]
+    fn filled(len: usize) -> Self {
+        debug_assert!(len <= 64);
+        if len == 64 {
+            u64::MAX
+        } else {
+            (1u64 << len) - 1
+        }
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        *self == 0
+    }
+
+    #[inline]
+    fn without(&self, index: usize) -> Self {
+        *self & !(1u64 << index)
+    }
+
+    #[inline]
+    fn first(&self) -> Option<usize> {
+        if *self == 0 {
+            None
+        } else {
+            Some(self.trailing_zeros() as usize)
+        }
+    }
+
+    #[inline]
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut bits = *self;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let index = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(index)
+        })
+    }
+}
+
+/// A bitset of any length, for the refs over 64.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct RefsBitSet {
+    words: Box<[u64]>,
+}
+
+impl RefsMask for RefsBitSet {
+    #[inline]
+    fn filled(len: usize) -> Self {
+        let mut words = vec![u64::MAX; len / 64];
+        if !len.is_multiple_of(64) {
+            words.push((1u64 << (len % 64)) - 1);
+        }
+        Self {
+            words: words.into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    #[inline]
+    fn without(&self, index: usize) -> Self {
+        let mut words = self.words.clone();
+        words[index / 64] &= !(1u64 << (index % 64));
+        Self { words }
+    }
+
+    #[inline]
+    fn first(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .find_map(|(offset, word)| word.first().map(|index| offset * 64 + index))
+    }
+
+    #[inline]
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(offset, word)| word.indices().map(move |index| offset * 64 + index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::placements::refs_mask::{RefsBitSet, RefsMask};
+
+    #[test]
+    fn same_as_u64() {
+        for len in [0, 1, 5, 63, 64] {
+            let small = u64::filled(len);
+            let large = RefsBitSet::filled(len);
+            assert!(small.indices().eq(large.indices()));
+            assert!(small.indices().eq(0..len));
+            assert_eq!(small.first(), large.first());
+
+            if 3 < len {
+                let small = small.without(3).without(0);
+                let large = large.without(3).without(0);
+                assert!(small.indices().eq(large.indices()));
+            }
+        }
+    }
+
+    #[test]
+    fn large() {
+        let mask = RefsBitSet::filled(130);
+        assert!(mask.indices().eq(0..130));
+
+        let mask = (0..130)
+            .filter(|index| index % 3 != 0)
+            .fold(mask, |mask, index| mask.without(index));
+        assert!(mask.indices().eq((0..130).step_by(3)));
+        assert_eq!(mask.without(0).first(), Some(3));
+        assert!(!mask.is_empty());
+
+        let mask = (0..130).fold(mask, |mask, index| mask.without(index));
+        assert!(mask.is_empty());
+        assert!(RefsBitSet::filled(0).is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+use crate::boards::{Board64, BoardOp};
+use crate::pieces::ShapeCounter;
+use crate::placements::PlacedPieceBlocks;
+use crate::{CancelToken, CancelWatch, GenerateInstruction, SearchStatus};
+
+/// An exact-cover solver that finds the sets of placed pieces covering the free cells below the height without overlaps.
+/// It's the core of perfect clear searches: each tiling can then be checked for stackability with `PlacedPieceBlocksFlow`.
+///
+/// It backtracks on bitboards, always covering the free cell at the lowest of the leftmost column first.
+/// On the way, it prunes the free cells split into groups that don't have a multiple of 4 cells.
+/// Only the candidates that fit in the free cells are used. The candidates are expected to have no duplicates,
+/// such as ones from `PlacedPiece::make_canonical_on_board_iter()`; otherwise, the same tiling is yielded more than once.
+/// ```
+/// use std::str::FromStr;
+/// use bitris::prelude::*;
+///
+/// let board = Board64::from_str("
+///     XXXXXX....
+///     XXXXXX....
+/// ").unwrap();
+/// let candidates: Vec<PlacedPieceBlocks> = PlacedPiece::make_canonical_on_board_iter(board, 2)
+///     .map(PlacedPieceBlocks::from)
+///     .collect();
+/// let solver = TilingSolver::new(board, 2, &candidates);
+///
+/// let shapes = ShapeCounter::from_iter([Shape::O, Shape::O, Shape::I, Shape::I]);
+/// let tilings = solver.find_all(shapes);
+/// assert_eq!(tilings.len(), 2); // OO or II
+/// assert_eq!(solver.count(ShapeCounter::from_iter([Shape::O, Shape::O, Shape::I])), 1);
+///
+/// let move_rules = MoveRules::srs(AllowMove::Softdrop);
+/// assert!(tilings.iter().any(|tiling| {
+///     PlacedPieceBlocksFlow::find_one_stackable(board, tiling, &move_rules, bl(4, 20)).is_some()
+/// }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TilingSolver<'a> {
+    pub initial_board: Board64,
+    pub height: u32,
+
+    // The candidates grouped by the first cell, which is the lowest in the leftmost column of the piece.
+    // The index of the cell is `x * 64 + y`.
+    candidates_by_cell: Vec<Vec<(Board64, &'a PlacedPieceBlocks)>>,
+}
+
+impl<'a> TilingSolver<'a> {
+    /// Makes the solver for the free cells of the board below the height.
+    /// Panics if the height exceeds 64.
+    pub fn new(initial_board: Board64, height: u32, candidates: &'a [PlacedPieceBlocks]) -> Self {
+        assert!(height <= 64, "the height supports up to 64.");
+
+        let free = Self::free_cells(&initial_board, height);
+        let mut candidates_by_cell = vec![Vec::new(); 10 * 64];
+        for placed_piece_blocks in candidates {
+            let mut cells = Board64::blank();
+            placed_piece_blocks.set_all(&mut cells);
+
+            let fits = cells
+                .cols
+                .iter()
+                .zip(free.cols.iter())
+                .all(|(cell, free)| cell & !free == 0);
+            if !fits {
+                continue;
+            }
+
+            let x = cells.cols.iter().position(|&col| col != 0).unwrap();
+            let y = cells.cols[x].trailing_zeros() as usize;
+            candidates_by_cell[x * 64 + y].push((cells, placed_piece_blocks));
+        }
+
+        Self {
+            initial_board,
+            height,
+            candidates_by_cell,
+        }
+    }
+
+    // Returns true if each group of the free cells has a multiple of 4 cells, since a piece cannot span groups.
+    // The pieces can span the lines cleared on the way, so all cells in a column are in the same group,
+    // and the adjacent columns sharing a free row are joined.
+    #[inline]
+    fn has_fillable_groups(free: &Board64) -> bool {
+        let mut count = 0;
+        let mut prev = 0u64;
+        for &col in &free.cols {
+            if prev & col == 0 {
+                if count % 4 != 0 {
+                    return false;
+                }
+                count = 0;
+            }
+            count += col.count_ones();
+            prev = col;
+        }
+        count % 4 == 0
+    }
+
+    #[inline]
+    fn free_cells(board: &Board64, height: u32) -> Board64 {
+        let mask = if height < 64 {
+            (1u64 << height) - 1
+        } else {
+            u64::MAX
+        };
+        Board64::new(board.cols.map(|col| !col & mask))
+    }
+
+    /// Visits each tiling using the shapes. The shapes can remain after covering.
+    /// The placed pieces of a tiling are in the order of the covered cells, not the order to stack.
+    #[inline]
+    pub fn for_each<C: FnMut(&Vec<&'a PlacedPieceBlocks>) -> GenerateInstruction>(
+        &self,
+        shapes: ShapeCounter,
+        callback: C,
+    ) {
+        self.search(shapes, None, callback);
+    }
+
+    /// It's similar to `for_each()` except that the search stops when the token is cancelled.
+    /// The tilings visited before the stop are the partial results, and the returned status tells whether they're all.
+    #[inline]
+    pub fn for_each_with_cancel<C: FnMut(&Vec<&'a PlacedPieceBlocks>) -> GenerateInstruction>(
+        &self,
+        shapes: ShapeCounter,
+        cancel: &CancelToken,
+        callback: C,
+    ) -> SearchStatus {
+        let watch = CancelWatch::new(cancel);
+        self.search(shapes, Some(&watch), callback);
+        watch.status()
+    }
+
+    // Returns the count of the pruned nodes.
+    fn search<C: FnMut(&Vec<&'a PlacedPieceBlocks>) -> GenerateInstruction>(
+        &self,
+        shapes: ShapeCounter,
+        watch: Option<&CancelWatch>,
+        mut callback: C,
+    ) -> usize {
+        struct Builder<'a, 'b, C: FnMut(&Vec<&'b PlacedPieceBlocks>) -> GenerateInstruction> {
+            candidates_by_cell: &'a Vec<Vec<(Board64, &'b PlacedPieceBlocks)>>,
+            results: Vec<&'b PlacedPieceBlocks>,
+            watch: Option<&'a CancelWatch<'a>>,
+            callback: &'a mut C,
+            pruned: usize,
+        }
+
+        impl<'b, C: FnMut(&Vec<&'b PlacedPieceBlocks>) -> GenerateInstruction> Builder<'_, 'b, C> {
+            fn build(&mut self, free: Board64, shapes: ShapeCounter) -> GenerateInstruction {
+                if self.watch.is_some_and(|watch| watch.should_stop()) {
+                    return GenerateInstruction::Stop;
+                }
+
+                if !TilingSolver::has_fillable_groups(&free) {
+                    self.pruned += 1;
+                    return GenerateInstruction::Continue;
+                }
+
+                let Some(x) = free.cols.iter().position(|&col| col != 0) else {
+                    return (self.callback)(&self.results);
+                };
+                let y = free.cols[x].trailing_zeros() as usize;
+
+                for &(cells, placed_piece_blocks) in &self.candidates_by_cell[x * 64 + y] {
+                    let shape = placed_piece_blocks.placed_piece.piece.shape;
+                    if shapes.count(shape) == 0 {
+                        continue;
+                    }
+
+                    let fits = cells
+                        .cols
+                        .iter()
+                        .zip(free.cols.iter())
+                        .all(|(cell, free)| cell & !free == 0);
+                    if !fits {
+                        continue;
+                    }
+
+                    let next_free =
+                        Board64::new(std::array::from_fn(|x| free.cols[x] & !cells.cols[x]));
+
+                    self.results.push(placed_piece_blocks);
+                    let instruction = self.build(next_free, shapes - shape);
+                    self.results.pop();
+
+                    if instruction == GenerateInstruction::Stop {
+                        return GenerateInstruction::Stop;
+                    }
+                }
+
+                GenerateInstruction::Continue
+            }
+        }
+
+        let free = Self::free_cells(&self.initial_board, self.height);
+        if (shapes.len() as u32) * 4 < free.count_blocks() {
+            return 0;
+        }
+
+        let mut builder = Builder {
+            candidates_by_cell: &self.candidates_by_cell,
+            results: Vec::with_capacity(free.count_blocks() as usize / 4),
+            watch,
+            callback: &mut callback,
+            pruned: 0,
+        };
+        builder.build(free, shapes);
+        builder.pruned
+    }
+
+    /// Returns all tilings using the shapes. See `for_each()` for the details.
+    pub fn find_all(&self, shapes: ShapeCounter) -> Vec<Vec<&'a PlacedPieceBlocks>> {
+        let mut tilings = Vec::new();
+        self.for_each(shapes, |tiling| {
+            tilings.push(tiling.clone());
+            GenerateInstruction::Continue
+        });
+        tilings
+    }
+
+    /// Returns the tilings found before the token is cancelled, and the status of the search.
+    /// ```
+    /// use std::str::FromStr;
+    /// use std::time::Duration;
+    /// use bitris::prelude::*;
+    ///
+    /// let board = Board64::from_str("
+    ///     XXXXXX....
+    ///     XXXXXX....
+    /// ").unwrap();
+    /// let candidates: Vec<PlacedPieceBlocks> = PlacedPiece::make_canonical_on_board_iter(board, 2)
+    ///     .map(PlacedPieceBlocks::from)
+    ///     .collect();
+    /// let solver = TilingSolver::new(board, 2, &candidates);
+    /// let shapes = ShapeCounter::from_iter([Shape::O, Shape::O, Shape::I, Shape::I]);
+    ///
+    /// let token = CancelToken::with_timeout(Duration::from_secs(60));
+    /// let (tilings, status) = solver.find_all_with_cancel(shapes, &token);
+    /// assert_eq!((tilings.len(), status), (2, SearchStatus::Completed));
+    ///
+    /// let token = CancelToken::with_timeout(Duration::ZERO);
+    /// let (tilings, status) = solver.find_all_with_cancel(shapes, &token);
+    /// assert_eq!((tilings.len(), status), (0, SearchStatus::TimedOut));
+    /// ```
+    pub fn find_all_with_cancel(
+        &self,
+        shapes: ShapeCounter,
+        cancel: &CancelToken,
+    ) -> (Vec<Vec<&'a PlacedPieceBlocks>>, SearchStatus) {
+        let mut tilings = Vec::new();
+        let status = self.for_each_with_cancel(shapes, cancel, |tiling| {
+            tilings.push(tiling.clone());
+            GenerateInstruction::Continue
+        });
+        (tilings, status)
+    }
+
+    /// Returns the first tiling using the shapes, or None if no tilings exist.
+    pub fn find_one(&self, shapes: ShapeCounter) -> Option<Vec<&'a PlacedPieceBlocks>> {
+        let mut found = None;
+        self.for_each(shapes, |tiling| {
+            found = Some(tiling.clone());
+            GenerateInstruction::Stop
+        });
+        found
+    }
+
+    /// Returns the count of tilings using the shapes.
+    pub fn count(&self, shapes: ShapeCounter) -> usize {
+        let mut count = 0;
+        self.for_each(shapes, |_| {
+            count += 1;
+            GenerateInstruction::Continue
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fxhash::FxHashSet;
+
+    use crate::prelude::*;
+
+    fn candidates(board: Board64, height: usize) -> Vec<PlacedPieceBlocks> {
+        PlacedPiece::make_canonical_on_board_iter(board, height)
+            .map(PlacedPieceBlocks::from)
+            .collect()
+    }
+
+    #[test]
+    fn exact_covers() {
+        let board = Board64::from_str(
+            "\
+            XXXXX.....\
+            XXXXX.....\
+            XXXXXXX...\
+            XXXXXXX...\
+            ",
+        )
+        .unwrap();
+        let candidates = candidates(board, 4);
+        let solver = TilingSolver::new(board, 4, &candidates);
+
+        let shapes = ShapeCounter::from_iter(Shape::all_iter()) + Shape::I + Shape::O;
+        let tilings = solver.find_all(shapes);
+        assert!(!tilings.is_empty());
+        assert_eq!(tilings.len(), solver.count(shapes));
+
+        let mut keys = FxHashSet::default();
+        for tiling in &tilings {
+            assert_eq!(tiling.len(), 4);
+            let mut merged = board;
+            for placed_piece_blocks in tiling {
+                let mut cells = Board64::blank();
+                placed_piece_blocks.set_all(&mut cells);
+                assert!((0..10).all(|x| merged.cols[x] & cells.cols[x] == 0));
+                placed_piece_blocks.set_all(&mut merged);
+            }
+            assert_eq!(merged, Board64::filled_up_to(4));
+            assert!(shapes.contains_all(
+                &tiling
+                    .iter()
+                    .map(|it| it.placed_piece.piece.shape)
+                    .collect()
+            ));
+
+            let mut key = tiling.iter().map(|it| it.placed_piece).collect::<Vec<_>>();
+            key.sort();
+            assert!(keys.insert(key));
+        }
+
+        assert_eq!(solver.find_one(shapes), tilings.first().cloned());
+    }
+
+    #[test]
+    fn shape_constraints() {
+        let board = Board64::from_str(
+            "\
+            XXXXXX....\
+            XXXXXX....\
+            ",
+        )
+        .unwrap();
+        let candidates = candidates(board, 2);
+        let solver = TilingSolver::new(board, 2, &candidates);
+
+        assert_eq!(
+            solver.count(ShapeCounter::from_iter([Shape::O, Shape::O])),
+            1
+        );
+        assert_eq!(
+            solver.count(ShapeCounter::from_iter([Shape::L, Shape::L])),
+            1
+        );
+        assert_eq!(
+            solver.count(ShapeCounter::from_iter([Shape::J, Shape::J])),
+            1
+        );
+        assert_eq!(
+            solver.count(ShapeCounter::from_iter([Shape::L, Shape::J])),
+            0
+        );
+        assert_eq!(
+            solver.count(ShapeCounter::from_iter([Shape::S, Shape::Z])),
+            0
+        );
+        assert_eq!(solver.count(ShapeCounter::one(Shape::O)), 0);
+        assert_eq!(
+            solver.find_one(ShapeCounter::from_iter([Shape::T, Shape::T])),
+            None
+        );
+    }
+
+    #[test]
+    fn intercepted_pieces() {
+        // The filled line separates the free cells, and vertical I spans it.
+        let board = Board64::from_str(
+            "\
+            XXXXXXXXX.\
+            XXXXXXXXXX\
+            XXXXXXXXX.\
+            XXXXXXXXX.\
+            XXXXXXXXX.\
+            ",
+        )
+        .unwrap();
+        let candidates = candidates(board, 5);
+        let solver = TilingSolver::new(board, 5, &candidates);
+        let tilings = solver.find_all(ShapeCounter::one(Shape::I));
+        assert_eq!(tilings.len(), 1);
+        assert_eq!(tilings[0][0].placed_piece.ys.to_vec(), vec![0, 1, 2, 4]);
+
+        let flow = PlacedPieceBlocksFlow::find_one_placeable(board, &tilings[0]);
+        assert!(flow.is_some());
+    }
+
+    #[test]
+    fn pruned_groups() {
+        // The flat T or L at the bottom left fills a column, which splits the cells on the left from the others.
+        let board = Board64::from_str(
+            "\
+            ......XXXX\
+            ......XXXX\
+            ",
+        )
+        .unwrap();
+        let candidates = candidates(board, 2);
+        let solver = TilingSolver::new(board, 2, &candidates);
+        let shapes = ShapeCounter::from_iter([Shape::T, Shape::L, Shape::L, Shape::O, Shape::O]);
+
+        let mut tilings = Vec::new();
+        let pruned = solver.search(shapes, None, |tiling| {
+            tilings.push(tiling.clone());
+            GenerateInstruction::Continue
+        });
+        assert!(0 < pruned);
+        assert_eq!(tilings, solver.find_all(shapes));
+        assert!(!tilings.is_empty());
+    }
+
+    #[test]
+    fn fillable_groups() {
+        assert!(TilingSolver::has_fillable_groups(&Board64::blank()));
+        assert!(TilingSolver::has_fillable_groups(&Board64::filled_up_to(4)));
+
+        // The gap of the lines can be spanned by vertical I.
+        let mut free = Board64::blank();
+        free.cols[0] = 0b10111;
+        assert!(TilingSolver::has_fillable_groups(&free));
+
+        free.cols[2] = 0b11;
+        assert!(!TilingSolver::has_fillable_groups(&free));
+        free.cols[3] = 0b11;
+        assert!(TilingSolver::has_fillable_groups(&free));
+        free.cols[4] = 0b100;
+        assert!(!TilingSolver::has_fillable_groups(&free));
+    }
+
+    #[test]
+    fn cancel() {
+        let board = Board64::from_str(
+            "\
+            XXXXX.....\
+            XXXXX.....\
+            XXXXXXX...\
+            XXXXXXX...\
+            ",
+        )
+        .unwrap();
+        let candidates = candidates(board, 4);
+        let solver = TilingSolver::new(board, 4, &candidates);
+        let shapes = ShapeCounter::from_iter(Shape::all_iter()) + Shape::I + Shape::O;
+        assert!(1 < solver.count(shapes));
+
+        let token = CancelToken::new();
+        let mut visited = 0;
+        let status = solver.for_each_with_cancel(shapes, &token, |_| {
+            visited += 1;
+            token.cancel();
+            GenerateInstruction::Continue
+        });
+        assert_eq!((visited, status), (1, SearchStatus::Cancelled));
+
+        let (tilings, status) = solver.find_all_with_cancel(shapes, &CancelToken::new());
+        assert_eq!(tilings, solver.find_all(shapes));
+        assert_eq!(status, SearchStatus::Completed);
+    }
+
+    #[test]
+    fn blank() {
+        let solver = TilingSolver::new(Board64::filled_up_to(4), 4, &[]);
+        assert_eq!(
+            solver.find_all(ShapeCounter::empty()),
+            vec![Vec::<&PlacedPieceBlocks>::new()]
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use fxhash::FxHashMap;
+use thiserror::Error;
+use tinyvec::ArrayVec;
+
+use crate::boards::{Board64, BoardHasher};
+use crate::pieces::{Orientation, Piece, Shape, ShapeCounter};
+use crate::placements::{PlacedPiece, PlacedPieceBlocks, TilingSolver};
+use crate::GenerateInstruction;
+
+/// A collection of errors that occur when saving or loading the tiling caches.
+#[derive(Error, Debug)]
+pub enum TilingCacheError {
+    #[error("Failed to read or write the cache: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The data is not a tiling cache.")]
+    InvalidMagic,
+    #[error("The version {found} is not supported, expected {expected}.")]
+    UnsupportedVersion { found: u32, expected: u32 },
+    #[error("The checksum is mismatched, the data is broken.")]
+    ChecksumMismatch,
+    #[error("The data is malformed: {0}")]
+    Malformed(&'static str),
+}
+
+/// The precomputed tilings of the free cells below the height, saved to and loaded from a binary file.
+/// Computing the tilings takes a long time, so the cache makes repeated searches start instantly.
+///
+/// The tilings cover the free cells by any shapes, so filter them by the pieces to use with `tilings_using()`.
+///
+/// The format is little-endian, and is checked by the version and the checksum:
+/// * The magic `BTTC` and the version as u32.
+/// * The height as u32, and the 10 columns of the board as u64.
+/// * The count of placed pieces as u32, and each as the shape, the orientation, lx, and the count of ys as u8, followed by ys as u8.
+/// * The count of tilings as u32, and each as the count of pieces as u8, followed by the indices of placed pieces as u32.
+/// * The checksum of all preceding bytes as u64, computed by `BoardHasher`.
+/// ```
+/// use std::str::FromStr;
+/// use bitris::prelude::*;
+///
+/// let board = Board64::from_str("
+///     XXXXXX....
+///     XXXXXX....
+/// ").unwrap();
+/// let cache = TilingCache::compute(board, 2);
+///
+/// let mut bytes = Vec::new();
+/// cache.write_to(&mut bytes).unwrap();
+/// let loaded = TilingCache::read_from(bytes.as_slice()).unwrap();
+/// assert_eq!(loaded, cache);
+///
+/// let tilings = loaded.tilings_using(ShapeCounter::from_iter([Shape::O, Shape::O]));
+/// assert_eq!(tilings.len(), 1);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TilingCache {
+    pub board: Board64,
+    pub height: u32,
+    pub placed_piece_blocks: Vec<PlacedPieceBlocks>,
+    /// The indices of `placed_piece_blocks` for each tiling.
+    pub tilings: Vec<Vec<u32>>,
+}
+
+impl TilingCache {
+    const MAGIC: [u8; 4] = *b"BTTC";
+
+    /// The version of the format. It changes when the format is not compatible.
+    pub const VERSION: u32 = 1;
+
+    /// Computes all tilings of the free cells below the height on the board.
+    /// Panics if the height exceeds 64.
+    pub fn compute(board: Board64, height: u32) -> Self {
+        let candidates: Vec<PlacedPieceBlocks> =
+            PlacedPiece::make_canonical_on_board_iter(board, height as usize)
+                .map(PlacedPieceBlocks::from)
+                .collect();
+        let indices: FxHashMap<PlacedPiece, u32> = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, it)| (it.placed_piece, index as u32))
+            .collect();
+
+        let mut tilings = Vec::new();
+        {
+            let solver = TilingSolver::new(board, height, &candidates);
+            let count = (10 * height.min(64)) as usize / 4;
+            let shapes = Shape::all_iter()
+                .flat_map(|shape| std::iter::repeat_n(shape, count.min(255)))
+                .collect::<ShapeCounter>();
+            solver.for_each(shapes, |tiling| {
+                tilings.push(tiling.iter().map(|it| indices[&it.placed_piece]).collect());
+                GenerateInstruction::Continue
+            });
+        }
+
+        Self {
+            board,
+            height,
+            placed_piece_blocks: candidates,
+            tilings,
+        }
+    }
+
+    /// Returns true if the cache is computed for the board and the height.
+    #[inline]
+    pub fn is_for(&self, board: &Board64, height: u32) -> bool {
+        self.board == *board && self.height == height
+    }
+
+    /// Returns an iterator of all tilings.
+    #[inline]
+    pub fn tilings_iter(&self) -> impl Iterator<Item = Vec<&PlacedPieceBlocks>> + '_ {
+        self.tilings.iter().map(|tiling| {
+            tiling
+                .iter()
+                .map(|&index| &self.placed_piece_blocks[index as usize])
+                .collect()
+        })
+    }
+
+    /// Returns the tilings that can be made by the shapes. The shapes can remain.
+    pub fn tilings_using(&self, shapes: ShapeCounter) -> Vec<Vec<&PlacedPieceBlocks>> {
+        self.tilings_iter()
+            .filter(|tiling| {
+                let used = tiling
+                    .iter()
+                    .map(|it| it.placed_piece.piece.shape)
+                    .collect::<ShapeCounter>();
+                shapes.contains_all(&used)
+            })
+            .collect()
+    }
+
+    /// Writes the cache in the binary format.
+    pub fn write_to(&self, mut writer: impl Write) -> Result<(), TilingCacheError> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&Self::MAGIC);
+        bytes.extend_from_slice(&Self::VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        for col in self.board.cols {
+            bytes.extend_from_slice(&col.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.placed_piece_blocks.len() as u32).to_le_bytes());
+        for placed_piece_blocks in &self.placed_piece_blocks {
+            let placed_piece = placed_piece_blocks.placed_piece;
+            bytes.push(placed_piece.piece.shape as u8);
+            bytes.push(placed_piece.piece.orientation as u8);
+            bytes.push(placed_piece.lx);
+            bytes.push(placed_piece.ys.len() as u8);
+            bytes.extend_from_slice(&placed_piece.ys);
+        }
+
+        bytes.extend_from_slice(&(self.tilings.len() as u32).to_le_bytes());
+        for tiling in &self.tilings {
+            bytes.push(tiling.len() as u8);
+            for index in tiling {
+                bytes.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+
+        bytes.extend_from_slice(&checksum(&bytes).to_le_bytes());
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Reads the cache in the binary format.
+    /// Returns an error if the version is different or the data is broken.
+    pub fn read_from(mut reader: impl Read) -> Result<Self, TilingCacheError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut cursor = ByteCursor { bytes: &bytes };
+        if cursor.take(4)? != Self::MAGIC {
+            return Err(TilingCacheError::InvalidMagic);
+        }
+        let version = cursor.u32()?;
+        if version != Self::VERSION {
+            return Err(TilingCacheError::UnsupportedVersion {
+                found: version,
+                expected: Self::VERSION,
+            });
+        }
+
+        if bytes.len() < 16 {
+            return Err(TilingCacheError::Malformed("too short"));
+        }
+        let (body, tail) = bytes.split_at(bytes.len() - 8);
+        if checksum(body) != u64::from_le_bytes(tail.try_into().unwrap()) {
+            return Err(TilingCacheError::ChecksumMismatch);
+        }
+        let mut cursor = ByteCursor { bytes: &body[8..] };
+
+        let height = cursor.u32()?;
+        if 64 < height {
+            return Err(TilingCacheError::Malformed("height"));
+        }
+        let mut cols = [0u64; 10];
+        for col in &mut cols {
+            *col = cursor.u64()?;
+        }
+
+        let placed_piece_count = cursor.u32()? as usize;
+        let mut placed_piece_blocks = Vec::with_capacity(placed_piece_count.min(body.len()));
+        for _ in 0..placed_piece_count {
+            placed_piece_blocks.push(PlacedPieceBlocks::make(cursor.placed_piece()?));
+        }
+
+        let tiling_count = cursor.u32()? as usize;
+        let mut tilings = Vec::with_capacity(tiling_count.min(body.len()));
+        for _ in 0..tiling_count {
+            let len = cursor.u8()? as usize;
+            let mut tiling = Vec::with_capacity(len);
+            for _ in 0..len {
+                let index = cursor.u32()?;
+                if placed_piece_count <= index as usize {
+                    return Err(TilingCacheError::Malformed("index of placed piece"));
+                }
+                tiling.push(index);
+            }
+            tilings.push(tiling);
+        }
+
+        if !cursor.bytes.is_empty() {
+            return Err(TilingCacheError::Malformed("trailing bytes"));
+        }
+
+        Ok(Self {
+            board: Board64::new(cols),
+            height,
+            placed_piece_blocks,
+            tilings,
+        })
+    }
+
+    /// Saves the cache to the file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TilingCacheError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads the cache from the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TilingCacheError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Loads the cache from the file if it's for the board and the height.
+    /// Otherwise, including when the file doesn't exist or is broken, computes the cache and saves it to the file.
+    pub fn load_or_compute(
+        path: impl AsRef<Path>,
+        board: Board64,
+        height: u32,
+    ) -> Result<Self, TilingCacheError> {
+        let path = path.as_ref();
+        if let Ok(cache) = Self::load(path) {
+            if cache.is_for(&board, height) {
+                return Ok(cache);
+            }
+        }
+
+        let cache = Self::compute(board, height);
+        cache.save(path)?;
+        Ok(cache)
+    }
+}
+
+#[inline]
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes
+        .chunks(8)
+        .fold(
+            BoardHasher::new().word(bytes.len() as u64),
+            |hasher, chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                hasher.word(u64::from_le_bytes(word))
+            },
+        )
+        .finish()
+}
+
+struct ByteCursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteCursor<'a> {
+    #[inline]
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TilingCacheError> {
+        if self.bytes.len() < len {
+            return Err(TilingCacheError::Malformed("unexpected end"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    #[inline]
+    fn u8(&mut self) -> Result<u8, TilingCacheError> {
+        Ok(self.take(1)?[0])
+    }
+
+    #[inline]
+    fn u32(&mut self) -> Result<u32, TilingCacheError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    #[inline]
+    fn u64(&mut self) -> Result<u64, TilingCacheError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn placed_piece(&mut self) -> Result<PlacedPiece, TilingCacheError> {
+        let shape = Shape::try_from(self.u8()? as usize)
+            .map_err(|_| TilingCacheError::Malformed("shape"))?;
+        let orientation = Orientation::all_iter()
+            .nth(self.u8()? as usize)
+            .ok_or(TilingCacheError::Malformed("orientation"))?;
+        let piece = Piece::new(shape, orientation);
+
+        let lx = self.u8()?;
+        if 10 < lx as u32 + piece.width() {
+            return Err(TilingCacheError::Malformed("lx"));
+        }
+
+        let len = self.u8()? as usize;
+        if len != piece.height() as usize {
+            return Err(TilingCacheError::Malformed("count of ys"));
+        }
+        let ys = self.take(len)?;
+        if ys.windows(2).any(|it| it[1] <= it[0]) || ys.iter().any(|&y| 64 <= y) {
+            return Err(TilingCacheError::Malformed("ys"));
+        }
+
+        Ok(PlacedPiece::new(
+            piece,
+            lx,
+            ys.iter().copied().collect::<ArrayVec<[u8; 4]>>(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::placements::tiling_cache::checksum;
+    use crate::prelude::*;
+
+    fn board() -> Board64 {
+        Board64::from_str(
+            "\
+            XXXXX.....\
+            XXXXX.....\
+            XXXXXXX...\
+            XXXXXXX...\
+            ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn same_as_solver() {
+        let board = board();
+        let cache = TilingCache::compute(board, 4);
+
+        let candidates = PlacedPiece::make_canonical_on_board_iter(board, 4)
+            .map(PlacedPieceBlocks::from)
+            .collect::<Vec<_>>();
+        let solver = TilingSolver::new(board, 4, &candidates);
+        for shapes in [
+            ShapeCounter::from_iter(Shape::all_iter()),
+            ShapeCounter::from_iter([Shape::I; 4]),
+            ShapeCounter::from_iter([Shape::T, Shape::T, Shape::L, Shape::J, Shape::O]),
+        ] {
+            let mut expected = solver.find_all(shapes);
+            let mut actual = cache.tilings_using(shapes);
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            actual.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let cache = TilingCache::compute(board(), 4);
+        let mut bytes = Vec::new();
+        cache.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..4], b"BTTC");
+        assert_eq!(TilingCache::read_from(bytes.as_slice()).unwrap(), cache);
+
+        let empty = TilingCache::compute(Board64::filled_up_to(4), 4);
+        assert_eq!(empty.tilings, vec![Vec::<u32>::new()]);
+        let mut bytes = Vec::new();
+        empty.write_to(&mut bytes).unwrap();
+        assert_eq!(TilingCache::read_from(bytes.as_slice()).unwrap(), empty);
+    }
+
+    #[test]
+    fn broken_data() {
+        let cache = TilingCache::compute(board(), 4);
+        let mut bytes = Vec::new();
+        cache.write_to(&mut bytes).unwrap();
+
+        let mut broken = bytes.clone();
+        broken[30] ^= 1;
+        assert!(matches!(
+            TilingCache::read_from(broken.as_slice()),
+            Err(TilingCacheError::ChecksumMismatch)
+        ));
+
+        let broken = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            TilingCache::read_from(broken),
+            Err(TilingCacheError::ChecksumMismatch)
+        ));
+
+        let mut broken = bytes.clone();
+        broken[0] = b'X';
+        assert!(matches!(
+            TilingCache::read_from(broken.as_slice()),
+            Err(TilingCacheError::InvalidMagic)
+        ));
+
+        let mut broken = bytes.clone();
+        broken[4] = 2;
+        assert!(matches!(
+            TilingCache::read_from(broken.as_slice()),
+            Err(TilingCacheError::UnsupportedVersion {
+                found: 2,
+                expected: 1
+            })
+        ));
+
+        // Passes the checksum, but the index is out of range.
+        let mut broken = bytes[..bytes.len() - 8].to_vec();
+        let len = broken.len();
+        broken[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+        let sum = checksum(&broken);
+        broken.extend_from_slice(&sum.to_le_bytes());
+        assert!(matches!(
+            TilingCache::read_from(broken.as_slice()),
+            Err(TilingCacheError::Malformed(_))
+        ));
+
+        assert!(matches!(
+            TilingCache::read_from(&b"BT"[..]),
+            Err(TilingCacheError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn files() {
+        let path =
+            std::env::temp_dir().join(format!("bitris-tiling-cache-{}.bin", std::process::id()));
+        let board = board();
+
+        let cache = TilingCache::load_or_compute(&path, board, 4).unwrap();
+        assert!(cache.is_for(&board, 4));
+        assert_eq!(TilingCache::load(&path).unwrap(), cache);
+
+        // The cache for another board is replaced.
+        let other = TilingCache::load_or_compute(&path, Board64::filled_up_to(2), 4).unwrap();
+        assert!(other.is_for(&Board64::filled_up_to(2), 4));
+        assert_eq!(TilingCache::load(&path).unwrap(), other);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            TilingCache::load(&path),
+            Err(TilingCacheError::Io(_))
+        ));
+    }
+}
@@ -5,7 +5,9 @@ use tinyvec::ArrayVec;
 use crate::boards::{BoardOp, Lines};
 use crate::coordinates::{Location, Offset, TrPosition};
 use crate::internal_macros::{add_member_for_from, forward_ref_from, forward_ref_op};
-use crate::pieces::{Orientation, Piece, PieceBlocks, PieceBlocksFactory, Shape};
+use crate::pieces::{
+    Orientation, Piece, PieceBlocks, PieceBlocksFactory, PieceBlocksTable, Shape, ToCcPosition,
+};
 use crate::placements::{BlPlacement, CcPlacement, PlacedPiece};
 use crate::{Rotate, Rotation};
 
@@ -27,6 +29,14 @@ impl TrPlacement {
         PieceBlocksFactory.get(self.piece)
     }
 
+    /// Returns the placement based on the center defined by the table.
+    /// It's the same as `to_cc_placement()` with the default table.
+    #[inline]
+    pub fn to_cc_placement_in(self, table: &impl PieceBlocksTable) -> CcPlacement {
+        let position = self.position.to_cc_position(table.get(self.piece));
+        CcPlacement::new(self.piece, position)
+    }
+
     #[inline]
     pub const fn shape(&self) -> Shape {
         self.piece.shape
@@ -155,7 +165,7 @@ impl TrPlacement {
     /// Returns the lines cleared if succeed.
     /// Returns None if failed to place.
     #[inline]
-    pub fn place_on_and_clear_lines(&self, board: &mut impl BoardOp) -> Option<Lines> {
+    pub fn place_on_and_clear_lines<B: BoardOp>(&self, board: &mut B) -> Option<B::Lines> {
         self.to_cc_placement().place_on_and_clear_lines(board)
     }
 
@@ -345,12 +355,18 @@ mod tests {
         Board64::blank()
     }
 
+    #[fixture]
+    pub fn board128() -> Board128 {
+        Board128::blank()
+    }
+
     #[template]
     #[rstest]
     #[case::board8(board8())]
     #[case::board16(board16())]
     #[case::board32(board32())]
     #[case::board64(board64())]
+    #[case::board128(board128())]
     fn all_boards(#[case] mut board: impl BoardOp) {}
 
     #[apply(all_boards)]
@@ -377,7 +393,7 @@ mod tests {
 
         assert_eq!(
             os.with(tr(9, 1)).place_on_and_clear_lines(&mut board),
-            Some(Lines::new(0b11))
+            Some(Lines::new(0b11).into())
         );
         assert_eq!(board.count_blocks(), 0);
     }
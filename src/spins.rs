@@ -0,0 +1,230 @@
+use crate::boards::BoardOp;
+use crate::coordinates::dd;
+use crate::internal_macros::enum_display;
+use crate::pieces::{Orientation, Shape};
+use crate::placements::CcPlacement;
+
+/// A collection of spins awarded for a placement.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum SpinKind {
+    #[default]
+    None,
+    Mini,
+    Regular,
+}
+
+enum_display! { SpinKind, has None,Mini,Regular }
+
+/// A collection of rules for spins by pieces other than T.
+/// T-spins are always detected by the 3-corner rule.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum AllSpinRule {
+    /// Only T-spins are awarded.
+    Disabled,
+    /// Immobile pieces after rotation are awarded as mini, as in TETR.IO.
+    #[default]
+    Mini,
+    /// Immobile pieces after rotation are awarded as regular spins.
+    Regular,
+}
+
+enum_display! { AllSpinRule, has Disabled,Mini,Regular }
+
+impl AllSpinRule {
+    /// Returns the spin awarded for locking the placement on the board.
+    /// `rotated` is whether the last action before locking was a rotation.
+    ///
+    /// A T-spin requires three of the four corners around the center to be occupied (outside the board counts),
+    /// and is a mini unless both corners on the pointing side are occupied.
+    /// The other pieces are spins when `CcPlacement::is_immobile()`.
+    /// ```
+    /// use std::str::FromStr;
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let board = Board64::from_str(
+    ///     "\
+    ///     XXX.X.....\
+    ///     XXX..XXXXX\
+    ///     XXXX.XXXXX\
+    ///     ",
+    /// ).unwrap();
+    /// let placement = piece!(SE).with(cc(3, 1));
+    /// assert_eq!(AllSpinRule::Mini.classify(&board, placement, true), SpinKind::Mini);
+    /// assert_eq!(AllSpinRule::Regular.classify(&board, placement, true), SpinKind::Regular);
+    /// assert_eq!(AllSpinRule::Disabled.classify(&board, placement, true), SpinKind::None);
+    /// assert_eq!(AllSpinRule::Mini.classify(&board, placement, false), SpinKind::None);
+    /// ```
+    pub fn classify(self, board: &impl BoardOp, placement: CcPlacement, rotated: bool) -> SpinKind {
+        if !rotated {
+            return SpinKind::None;
+        }
+
+        if placement.piece.shape == Shape::T {
+            return t_spin(board, placement);
+        }
+
+        if !placement.is_immobile(board) {
+            return SpinKind::None;
+        }
+
+        match self {
+            AllSpinRule::Disabled => SpinKind::None,
+            AllSpinRule::Mini => SpinKind::Mini,
+            AllSpinRule::Regular => SpinKind::Regular,
+        }
+    }
+}
+
+fn t_spin(board: &impl BoardOp, placement: CcPlacement) -> SpinKind {
+    let is_occupied = |dx: i32, dy: i32| {
+        let location = placement.position.to_location() + dd(dx, dy);
+        !board.test_access(location) || board.is_occupied_at(location)
+    };
+
+    // The corners are ordered so that the first two are on the pointing side.
+    let corners = match placement.piece.orientation {
+        Orientation::North => [(-1, 1), (1, 1), (-1, -1), (1, -1)],
+        Orientation::East => [(1, 1), (1, -1), (-1, 1), (-1, -1)],
+        Orientation::South => [(-1, -1), (1, -1), (-1, 1), (1, 1)],
+        Orientation::West => [(-1, 1), (-1, -1), (1, 1), (1, -1)],
+    }
+    .map(|(dx, dy)| is_occupied(dx, dy));
+
+    if corners.iter().filter(|&&it| it).count() < 3 {
+        return SpinKind::None;
+    }
+
+    if corners[0] && corners[1] {
+        SpinKind::Regular
+    } else {
+        SpinKind::Mini
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::piece;
+    use crate::prelude::*;
+
+    #[test]
+    fn t_spin() {
+        let board = Board64::from_str(
+            "\
+            XXX.......\
+            XX...XXXXX\
+            XXX.XXXXXX\
+            ",
+        )
+        .unwrap();
+        let rule = AllSpinRule::Mini;
+        assert_eq!(
+            rule.classify(&board, piece!(TS).with(cc(3, 1)), true),
+            SpinKind::Regular
+        );
+        assert_eq!(
+            rule.classify(&board, piece!(TS).with(cc(3, 1)), false),
+            SpinKind::None
+        );
+
+        // Only one corner on the pointing side.
+        let board = Board64::from_str(
+            "\
+            XXX.X.....\
+            XX...XXXXX\
+            XX..XXXXXX\
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            rule.classify(&board, piece!(TS).with(cc(3, 1)), true),
+            SpinKind::Mini
+        );
+
+        // T-spins don't depend on the all-spin rule.
+        assert_eq!(
+            AllSpinRule::Disabled.classify(&board, piece!(TS).with(cc(3, 1)), true),
+            SpinKind::Mini
+        );
+
+        // The floor counts as corners.
+        let board = Board64::from_str(
+            "\
+            X.........\
+            ..........\
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            rule.classify(&board, piece!(TN).with(cc(1, 0)), true),
+            SpinKind::Mini
+        );
+        assert_eq!(
+            rule.classify(&Board64::blank(), piece!(TN).with(cc(1, 0)), true),
+            SpinKind::None
+        );
+    }
+
+    #[test]
+    fn all_spin() {
+        let board = Board64::from_str(
+            "\
+            XX........\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            ",
+        )
+        .unwrap();
+
+        let placement = piece!(IE).with(cc(1, 2));
+        assert!(placement.is_immobile(&board));
+        assert_eq!(
+            AllSpinRule::Mini.classify(&board, placement, true),
+            SpinKind::Mini
+        );
+        assert_eq!(
+            AllSpinRule::Regular.classify(&board, placement, true),
+            SpinKind::Regular
+        );
+        assert_eq!(
+            AllSpinRule::Regular.classify(&board, placement, false),
+            SpinKind::None
+        );
+
+        // Can move up.
+        let board = Board64::from_str(
+            "\
+            X.........\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            X.XXXXXXXX\
+            ",
+        )
+        .unwrap();
+        assert!(!placement.is_immobile(&board));
+        assert_eq!(
+            AllSpinRule::Regular.classify(&board, placement, true),
+            SpinKind::None
+        );
+
+        let board = Board64::from_str(
+            "\
+            XXXXXXXXX.\
+            XXXXXXXX..\
+            XXXXXXXX..\
+            ",
+        )
+        .unwrap();
+        let placement = piece!(ON).with(cc(8, 0));
+        assert!(placement.is_immobile(&board));
+        assert!(placement.to_bl_placement().is_immobile(&board));
+        assert_eq!(
+            AllSpinRule::Mini.classify(&board, placement, true),
+            SpinKind::Mini
+        );
+    }
+}
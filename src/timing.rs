@@ -0,0 +1,411 @@
+use crate::boards::Board;
+use crate::internal_moves::{loaders, timing, MoveBits};
+use crate::placements::BlPlacement;
+use crate::{MoveError, MoveRules, Rotate, Rotation, RotationSystem, With};
+
+/// The handling settings that decide how many frames the inputs take.
+/// All durations are in frames.
+///
+/// The model is sequential: it applies one input at a time, and the next input starts after the previous one ends.
+/// So it doesn't overlap the inputs such as rotating while charging the DAS, and the plans may be slower than the inputs of a player.
+/// The gravity only decides the speed of the soft drop, and the piece doesn't fall by itself while waiting.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Handling {
+    /// Delayed Auto Shift: the frames from pressing left or right until the auto-repeat starts.
+    pub das: u32,
+    /// Auto Repeat Rate: the frames per cell in the auto-repeat. Zero moves to the wall at once.
+    pub arr: u32,
+    /// Soft Drop Factor: how many times faster than the gravity the soft drop is. None drops to the ground at once.
+    pub sdf: Option<u32>,
+    /// The frames per row by the gravity. It's only the base of the soft drop speed, not the natural fall.
+    pub gravity: u32,
+    /// Whether the hard drop is available. Without it, the piece waits for the lock delay on the ground.
+    pub harddrop: bool,
+    /// The frames until the piece locks on the ground without the hard drop.
+    pub lock_delay: u32,
+}
+
+impl Default for Handling {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            das: 10,
+            arr: 2,
+            sdf: Some(20),
+            gravity: 60,
+            harddrop: true,
+            lock_delay: 30,
+        }
+    }
+}
+
+impl Handling {
+    /// Returns the frames per row by the soft drop, or None if it drops to the ground at once.
+    /// It's at least one frame.
+    /// ```
+    /// use bitris::prelude::*;
+    /// let handling = Handling { gravity: 60, sdf: Some(20), ..Handling::default() };
+    /// assert_eq!(handling.soft_drop_frames_per_row(), Some(3));
+    /// let handling = Handling { gravity: 1, sdf: Some(20), ..Handling::default() };
+    /// assert_eq!(handling.soft_drop_frames_per_row(), Some(1));
+    /// ```
+    #[inline]
+    pub fn soft_drop_frames_per_row(&self) -> Option<u32> {
+        self.sdf.map(|sdf| self.gravity.div_ceil(sdf.max(1)).max(1))
+    }
+}
+
+/// A collection of inputs to carry the piece.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Input {
+    /// Moves one cell to the left by a tap.
+    #[default]
+    Left,
+    /// Moves one cell to the right by a tap.
+    Right,
+    /// Holds left until the piece moves the cells, using the auto-repeat.
+    DasLeft(u32),
+    /// Holds right until the piece moves the cells, using the auto-repeat.
+    DasRight(u32),
+    Cw,
+    Ccw,
+    /// Soft drops the rows.
+    SoftDrop(u32),
+    /// Drops to the ground and locks.
+    HardDrop,
+    /// Waits for the lock delay on the ground.
+    Lock,
+}
+
+/// The fastest inputs to lock the piece at the placement.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct InputPlan {
+    pub placement: BlPlacement,
+    pub inputs: Vec<Input>,
+    /// The frames from the spawn until the piece locks.
+    pub frames: u32,
+}
+
+impl<T> MoveRules<'_, T>
+where
+    T: RotationSystem,
+{
+    /// Returns the fastest inputs for each placement in `generate_all_moves()` with the handling.
+    /// The plans are sorted by the placements.
+    /// They are the fastest in the sequential model of `Handling`, not in the games that overlap the inputs.
+    ///
+    /// The placements are the ones reachable by the rules, but the paths are searched without the lock delay:
+    /// `move_reset_limit` and `gravity` of the rules don't restrict or move the piece on the path.
+    /// So a plan may take more resets on the ground than the rules allow.
+    /// With `SpawnPolicy::InitialRotation`, the rotated spawns start with the rotation input, which takes no frames.
+    ///
+    /// Panics if the spawn is not placeable position.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let plans = rules.generate_input_plans(&Handling::default(), Board64::blank(), piece!(TN).with(bl(4, 20)));
+    /// assert_eq!(plans.len(), 34);
+    /// assert!(plans.iter().all(|plan| plan.inputs.last() == Some(&Input::HardDrop)));
+    /// ```
+    pub fn generate_input_plans<B: MoveBits>(
+        &self,
+        handling: &Handling,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Vec<InputPlan> {
+        let reachables = self.generate_all_moves_set(board, spawn);
+
+        let seeds = self
+            .spawns(board, spawn)
+            .into_iter()
+            .map(|placement| {
+                let input = [(Rotation::Cw, Input::Cw), (Rotation::Ccw, Input::Ccw)]
+                    .into_iter()
+                    .find(|&(rotation, _)| {
+                        placement != spawn && spawn.piece.rotate(rotation) == placement.piece
+                    })
+                    .map(|(_, input)| input);
+                (placement.to_cc_placement(), input)
+            })
+            .collect::<Vec<_>>();
+
+        let mut plans = timing::fastest_inputs(
+            self.rotation_system,
+            &loaders::to_free_space_block(&board),
+            spawn.piece.shape,
+            &seeds,
+            self.allow_move,
+            handling,
+        )
+        .into_iter()
+        .filter(|(placement, ..)| reachables.contains(*placement))
+        .map(|(placement, frames, inputs)| InputPlan {
+            placement: placement.to_bl_placement(),
+            inputs,
+            frames,
+        })
+        .collect::<Vec<_>>();
+        plans.sort_by_key(|plan| plan.placement);
+        plans
+    }
+
+    /// Returns the fastest inputs to lock the piece at the goal with the handling, or None if it cannot be reached.
+    /// Like `can_reach()`, the placements of the same form are also accepted.
+    /// The plan follows the same model as `generate_input_plans()`.
+    ///
+    /// Panics if the shapes of the goal and the spawn are different, or the spawn is not placeable position.
+    /// Use `try_find_fastest_inputs()` to get an error instead.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let handling = Handling { das: 2, arr: 0, ..Handling::default() };
+    /// let spawn = piece!(ON).with(bl(4, 20));
+    ///
+    /// let plan = rules.find_fastest_inputs(&handling, piece!(ON).with(bl(0, 0)), Board64::blank(), spawn).unwrap();
+    /// assert_eq!(plan.inputs, vec![Input::DasLeft(4), Input::HardDrop]);
+    /// assert_eq!(plan.frames, 4);
+    ///
+    /// let plan = rules.find_fastest_inputs(&handling, piece!(ON).with(bl(3, 0)), Board64::blank(), spawn).unwrap();
+    /// assert_eq!(plan.inputs, vec![Input::Left, Input::HardDrop]);
+    /// assert_eq!(plan.frames, 2);
+    /// ```
+    pub fn find_fastest_inputs<B: MoveBits>(
+        &self,
+        handling: &Handling,
+        goal: BlPlacement,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Option<InputPlan> {
+        assert_eq!(goal.piece.shape, spawn.piece.shape);
+
+        let goals = goal
+            .piece
+            .orientations_having_same_form()
+            .iter()
+            .map(|&orientation| goal.piece.shape.with(orientation).with(goal.position))
+            .collect::<Vec<_>>();
+
+        self.generate_input_plans(handling, board, spawn)
+            .into_iter()
+            .filter(|plan| goals.contains(&plan.placement))
+            .min_by_key(|plan| plan.frames)
+    }
+
+    /// It's similar to `find_fastest_inputs()` except that it returns an error instead of panicking.
+    /// ```
+    /// use bitris::piece;
+    /// use bitris::prelude::*;
+    /// let rules = MoveRules::srs(AllowMove::Softdrop);
+    /// let spawn = piece!(TN).with(bl(4, 20));
+    /// assert_eq!(
+    ///     rules.try_find_fastest_inputs(&Handling::default(), piece!(SN).with(bl(0, 0)), Board64::blank(), spawn),
+    ///     Err(MoveError::ShapeMismatch { goal: Shape::S, spawn: Shape::T }),
+    /// );
+    /// ```
+    pub fn try_find_fastest_inputs<B: MoveBits>(
+        &self,
+        handling: &Handling,
+        goal: BlPlacement,
+        board: Board<B>,
+        spawn: BlPlacement,
+    ) -> Result<Option<InputPlan>, MoveError> {
+        self.validate_goal_and_spawn(goal, &board, spawn)?;
+        Ok(self.find_fastest_inputs(handling, goal, board, spawn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::piece;
+    use crate::prelude::*;
+
+    #[test]
+    fn all_moves_have_plans() {
+        let board = Board64::from_str(
+            "\
+            ....X.....\
+            ..........\
+            XX.....XXX\
+            X.....XXXX\
+            XX..XXXXXX\
+            ",
+        )
+        .unwrap();
+        let handling = Handling::default();
+        for shape in Shape::all_iter() {
+            let spawn = GameRules::guideline().spawn(shape);
+            let rules = MoveRules::srs(AllowMove::Softdrop);
+            let moves = rules.generate_all_moves(board, spawn);
+            let plans = rules.generate_input_plans(&handling, board, spawn);
+            assert_eq!(plans.len(), moves.len());
+            assert!(plans
+                .iter()
+                .all(|plan| 0 < plan.frames && plan.inputs.last() == Some(&Input::HardDrop)));
+        }
+    }
+
+    #[test]
+    fn taps_and_das() {
+        let board = Board64::blank();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let spawn = piece!(ON).with(bl(4, 20));
+        let goal = piece!(ON).with(bl(0, 0));
+
+        let handling = Handling {
+            das: 3,
+            arr: 1,
+            ..Handling::default()
+        };
+        let plan = rules
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(
+            plan.inputs,
+            vec![
+                Input::Left,
+                Input::Left,
+                Input::Left,
+                Input::Left,
+                Input::HardDrop
+            ]
+        );
+        assert_eq!(plan.frames, 5);
+
+        let handling = Handling {
+            das: 1,
+            arr: 1,
+            ..Handling::default()
+        };
+        let plan = rules
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(plan.inputs, vec![Input::DasLeft(4), Input::HardDrop]);
+        assert_eq!(plan.frames, 5);
+
+        let handling = Handling {
+            das: 1,
+            arr: 1,
+            ..Handling::default()
+        };
+        let plan = rules
+            .find_fastest_inputs(&handling, piece!(ON).with(bl(1, 0)), board, spawn)
+            .unwrap();
+        assert_eq!(plan.inputs, vec![Input::DasLeft(3), Input::HardDrop]);
+        assert_eq!(plan.frames, 4);
+    }
+
+    #[test]
+    fn inputs_are_sequential() {
+        let board = Board64::blank();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let spawn = piece!(TN).with(bl(4, 20));
+        let goal = piece!(TE).with(bl(0, 0));
+        let handling = Handling {
+            das: 2,
+            arr: 0,
+            ..Handling::default()
+        };
+
+        // The rotation waits for the DAS instead of overlapping it.
+        let plan = rules
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(plan.inputs.len(), 3);
+        assert!(plan.inputs.contains(&Input::Cw));
+        assert_eq!(plan.frames, 1 + (1 + 2) + 1);
+    }
+
+    #[test]
+    fn without_harddrop() {
+        let board = Board64::blank();
+        let rules = MoveRules::srs(AllowMove::Softdrop);
+        let spawn = piece!(ON).with(bl(4, 20));
+        let goal = piece!(ON).with(bl(4, 0));
+
+        let handling = Handling {
+            sdf: None,
+            harddrop: false,
+            lock_delay: 30,
+            ..Handling::default()
+        };
+        let plan = rules
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(plan.inputs, vec![Input::SoftDrop(20), Input::Lock]);
+        assert_eq!(plan.frames, 31);
+
+        let handling = Handling {
+            sdf: Some(20),
+            gravity: 40,
+            harddrop: false,
+            lock_delay: 30,
+            ..Handling::default()
+        };
+        let plan = rules
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(plan.inputs, vec![Input::SoftDrop(20), Input::Lock]);
+        assert_eq!(plan.frames, 70);
+    }
+
+    #[test]
+    fn tuck_and_spin() {
+        let board = Board64::from_str(
+            "\
+            .XX..XXXXX\
+            .X..XXXXXX\
+            ",
+        )
+        .unwrap();
+        let spawn = piece!(SN).with(bl(4, 20));
+        let goal = piece!(SS).with(bl(2, 0));
+        let handling = Handling::default();
+
+        let plan = MoveRules::srs(AllowMove::Softdrop)
+            .find_fastest_inputs(&handling, goal, board, spawn)
+            .unwrap();
+        assert_eq!(plan.placement, piece!(SS).with(bl(2, 0)));
+        // Drops onto the stack, then spins into the hole by the two rotations.
+        assert_eq!(
+            plan.inputs,
+            vec![
+                Input::Left,
+                Input::SoftDrop(18),
+                Input::Cw,
+                Input::Left,
+                Input::Cw,
+                Input::HardDrop
+            ]
+        );
+        assert_eq!(plan.frames, 59);
+
+        assert_eq!(
+            MoveRules::srs(AllowMove::Harddrop).find_fastest_inputs(&handling, goal, board, spawn),
+            None
+        );
+    }
+
+    #[test]
+    fn initial_rotation() {
+        let mut board = Board64::blank();
+        board.set_at(Location::new(3, 20));
+        let spawn = piece!(IN).with(bl(3, 20));
+        let rules =
+            MoveRules::srs(AllowMove::Softdrop).with_spawn_policy(SpawnPolicy::InitialRotation);
+
+        let plan = rules
+            .find_fastest_inputs(
+                &Handling::default(),
+                piece!(IE).with(bl(5, 0)),
+                board,
+                spawn,
+            )
+            .unwrap();
+        assert_eq!(plan.inputs, vec![Input::Cw, Input::HardDrop]);
+        assert_eq!(plan.frames, 1);
+    }
+}